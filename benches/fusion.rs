@@ -0,0 +1,61 @@
+extern crate ReactiveRS;
+
+use std::time::{Instant};
+use std::result::{Result};
+
+use ReactiveRS::arrow::{Arrow,Immediate};
+use ReactiveRS::arrow::prim::{map,pause,fixpoint,identity};
+
+// Per-instant cost of a loop running a deep pipeline of maps before pausing,
+// built once with `bind` and once with `fuse`. The `bind` pipeline is not
+// fused, even though all its arrows are immediate.
+
+const INSTANTS : u32 = 100_000;
+
+macro_rules! pipeline {
+    ($op:ident, $first:expr, $($rest:expr),*) => {
+        $first $(.$op ($rest))*
+    }
+}
+
+fn report (name: &str, start: Instant) {
+    let elapsed = start.elapsed ();
+    let ns = elapsed.as_secs () * 1_000_000_000 + elapsed.subsec_nanos () as u64;
+    println! ("{:>8} : {:>6} ns/instant", name, ns / INSTANTS as u64);
+}
+
+fn step (i: u32) -> u32 { i.wrapping_mul (3) ^ 1 }
+
+fn main () {
+    let bound = pipeline! (bind,
+        map (step), map (step), map (step), map (step), identity (),
+        map (step), map (step), map (step), map (step), identity (),
+        map (step), map (step), map (step), map (step), identity (),
+        map (step), map (step), map (step), map (step), identity ()
+    );
+    let start = Instant::now ();
+    fixpoint (
+        map (|(n, i): (u32, u32)| { (n, i) })
+        .bind ( pause () )
+        .bind ( map (|(n, i): (u32, u32)| { (n + 1, i) }) )
+        .bind ( bound.second () )
+        .bind ( map (|(n, i): (u32, u32)| { if n < INSTANTS { Result::Ok ((n, i)) } else { Result::Err (i) } }) )
+    ).execute_seq ((0, 0));
+    report ("bind", start);
+
+    let fused = pipeline! (fuse,
+        map (step), map (step), map (step), map (step), identity (),
+        map (step), map (step), map (step), map (step), identity (),
+        map (step), map (step), map (step), map (step), identity (),
+        map (step), map (step), map (step), map (step), identity ()
+    );
+    let start = Instant::now ();
+    fixpoint (
+        map (|(n, i): (u32, u32)| { (n, i) })
+        .bind ( pause () )
+        .bind ( map (|(n, i): (u32, u32)| { (n + 1, i) }) )
+        .bind ( fused.second () )
+        .bind ( map (|(n, i): (u32, u32)| { if n < INSTANTS { Result::Ok ((n, i)) } else { Result::Err (i) } }) )
+    ).execute_seq ((0, 0));
+    report ("fuse", start);
+}
@@ -1,11 +1,17 @@
 
-use std::sync::{Arc,Mutex};
-use std::cell::{RefCell};
+use std::sync::{Arc,Mutex,Weak};
+use std::cell::{RefCell,Cell};
 use std::option::{Option};
 use std::mem::{swap};
 use std::marker::{PhantomData};
+use std::result::{Result};
+use std::fmt;
+use std::sync::atomic::{AtomicBool,Ordering};
 
 use runtime::{Runtime,SeqRuntime,ParRuntime,Continuation};
+use arrow::prim::{First,Second,SeqProd,Product,Fanout,ParFanout,first,second,seqprod,product,fanout,par_fanout};
+use arrow::prim::{AndThen,MapErr,OrElse,Catch,and_then,map_err,or_else,catch};
+use arrow::prim::{Output,Labelled,labelled,Pause,pause};
 
 pub mod prim;
 
@@ -24,6 +30,14 @@ where A: Send + 'static,
     fn call<C> (&self, rt: &mut Runtime, a: A, next: C)
     where C: Continuation<B> + Send;
 
+    fn describe (&self) -> Description {
+        Description::new ("arrow")
+    }
+
+    fn label (self, label: &str) -> Labelled<Self> {
+        labelled (self, label)
+    }
+
     fn execute_with_rt (self, rt: &mut Runtime, a: A) -> B {
         let val = Arc::new (Mutex::new (RefCell::new (Option::None)));
         let back = val.clone ();
@@ -56,6 +70,16 @@ where A: Send + 'static,
         self.execute_with_rt (&mut rt, a)
     }
 
+    fn instants<V> (self, a: A, out: &Output<V>) -> Instants<V>
+    where V: Send + 'static
+    {
+        let mut rt = SeqRuntime::new ();
+        rt.on_current_instant (Box::new (move |rt: &mut Runtime, ()| {
+            self.call (rt, a, |_: &mut Runtime, _: B| {});
+        }));
+        Instants {rt: rt, out: out.clone (), running: true}
+    }
+
     fn bind<C,Y> (self, y: Y) -> Bind<B,Self,Y>
     where C: Send + 'static,
           Y: Arrow<B,C> + 'static,
@@ -71,20 +95,166 @@ where A: Send + 'static,
         flatten (self)
     }
 
+    fn boxed (self) -> BoxedArrow<A,B>
+    where Self: Sync,
+    {
+        boxed (self)
+    }
+
+    fn shared (self) -> Shared<Self>
+    where Self: Sync,
+    {
+        shared (self)
+    }
+
+    fn first (self) -> First<Self> {
+        first (self)
+    }
+
+    fn second (self) -> Second<Self> {
+        second (self)
+    }
+
+    fn split<C,D,Y> (self, y: Y) -> SeqProd<Self,Y>
+    where C: Send + 'static,
+          D: Send + 'static,
+          Y: Arrow<C,D> + 'static,
+    {
+        seqprod (self, y)
+    }
+
+    fn par_split<C,D,Y> (self, y: Y) -> Product<Self,Y>
+    where C: Send + 'static,
+          D: Send + 'static,
+          Y: Arrow<C,D> + 'static,
+    {
+        product (self, y)
+    }
+
+    fn fanout<C,Y> (self, y: Y) -> Fanout<Self,Y>
+    where A: Clone,
+          C: Send + 'static,
+          Y: Arrow<A,C> + 'static,
+    {
+        fanout (self, y)
+    }
+
+    fn par_fanout<C,Y> (self, y: Y) -> ParFanout<Self,Y>
+    where A: Clone,
+          C: Send + 'static,
+          Y: Arrow<A,C> + 'static,
+    {
+        par_fanout (self, y)
+    }
+
+    fn pipe<C,Y> (self, y: Y) -> Bind<B,Bind<B,Self,Pause<B>>,Y>
+    where B: Sync,
+          C: Send + 'static,
+          Y: Arrow<B,C> + Sync + 'static,
+    {
+        self.bind (pause ()).bind (y)
+    }
+
+    fn and_then<C,D,E,Y> (self, y: Y) -> AndThen<C,Self,Y>
+    where Self: Arrow<A,Result<C,E>>,
+          C: Send + 'static,
+          D: Send + 'static,
+          E: Send + 'static,
+          Y: Arrow<C,Result<D,E>> + 'static,
+    {
+        and_then (self, y)
+    }
+
+    fn map_err<C,D,E,G> (self, f: G) -> MapErr<E,Self,G>
+    where Self: Arrow<A,Result<C,E>>,
+          C: Send + 'static,
+          D: Send + 'static,
+          E: Send + 'static,
+          G: Fn(E) -> D + Send + Sync + 'static,
+    {
+        map_err (self, f)
+    }
+
+    fn or_else<C,D,E,Y> (self, y: Y) -> OrElse<E,Self,Y>
+    where Self: Arrow<A,Result<C,E>>,
+          C: Send + 'static,
+          D: Send + 'static,
+          E: Send + 'static,
+          Y: Arrow<E,Result<C,D>> + 'static,
+    {
+        or_else (self, y)
+    }
+
+    fn catch<C,E,Y> (self, y: Y) -> Catch<E,Self,Y>
+    where Self: Arrow<A,Result<C,E>>,
+          C: Send + 'static,
+          E: Send + 'static,
+          Y: Arrow<E,C> + 'static,
+    {
+        catch (self, y)
+    }
+
+}
+
+// Continuation-passing closures would overlap with this impl, they are
+// wrapped by prim::primitive instead.
+
+impl<A,B,F> Arrow<A,B> for F
+where A: Send + 'static,
+      B: Send + 'static,
+      F: Fn(A) -> B + Send + 'static,
+{
+
+    fn call<C> (&self, rt: &mut Runtime, a: A, next: C)
+    where C: Continuation<B> + Send {
+        next.call (rt, self (a));
+    }
+
+    fn describe (&self) -> Description {
+        Description::new ("fn")
+    }
+
 }
 
-// impl<A,B,F> Arrow<A,B> for F
-// where A: 'static,
-//       B: 'static,
-//       F: Fn(A) -> B + 'static,
-// {
-//     
-//     fn call<C> (&self, rt: &mut Runtime, a: A, next: C)
-//     where C: Continuation<B> {
-//         next.call (rt, self (a))
-//     }
-// 
-// }
+//  ___                              _ _       _       
+// |_ _|_ __ ___  _ __ ___   ___  __| (_) __ _| |_ ___ 
+//  | || '_ ` _ \| '_ ` _ \ / _ \/ _` | |/ _` | __/ _ \
+//  | || | | | | | | | | | |  __/ (_| | | (_| | ||  __/
+// |___|_| |_| |_|_| |_| |_|\___|\__,_|_|\__,_|\__\___|
+//                                                     
+
+// Arrows which never suspend nor touch the runtime: they can be chained with
+// `fuse`, which runs them inline instead of going through continuations.
+// Apart from the rewrites of `Identity::bind`, `Value::map` and `Map::map`,
+// `bind` always goes through a continuation, even between immediate arrows.
+
+pub trait Immediate<A,B> : Arrow<A,B>
+where A: Send + 'static,
+      B: Send + 'static,
+{
+
+    fn apply (&self, a: A) -> B;
+
+    fn fuse<C,Y> (self, y: Y) -> Fuse<B,Self,Y>
+    where C: Send + 'static,
+          Y: Immediate<B,C>,
+    {
+        fuse (self, y)
+    }
+
+}
+
+impl<A,B,F> Immediate<A,B> for F
+where A: Send + 'static,
+      B: Send + 'static,
+      F: Fn(A) -> B + Send + 'static,
+{
+
+    fn apply (&self, a: A) -> B {
+        self (a)
+    }
+
+}
 
 //  ____  _           _ 
 // | __ )(_)_ __   __| |
@@ -114,6 +284,31 @@ where A: Send + 'static,
 }
 
 
+// Continuations of synchronous arrows are called directly, so a long chain of
+// binds grows the stack at each link. Past a given depth, the rest of the
+// chain is queued on the current instant instead, which unwinds the stack.
+
+const MAX_DEPTH : usize = 128;
+
+thread_local! {
+    static DEPTH : Cell<usize> = Cell::new (0);
+}
+
+fn bounce<F> (rt: &mut Runtime, f: F)
+where F: FnOnce(&mut Runtime) + Send + 'static
+{
+    let depth = DEPTH.with (|d| d.get ());
+    if depth < MAX_DEPTH {
+        DEPTH.with (|d| d.set (depth + 1));
+        f (rt);
+        DEPTH.with (|d| d.set (depth));
+    } else {
+        rt.on_current_instant (Box::new (move |rt: &mut Runtime, ()| {
+            f (rt);
+        }));
+    }
+}
+
 impl<A,B,C,X,Y> Arrow<A,C> for Bind<B,X,Y>
 where A: Send + 'static,
       B: Send + 'static,
@@ -126,10 +321,76 @@ where A: Send + 'static,
     where F: Continuation<C> + Send {
         let snd = self.snd.clone ();
         self.fst.call (rt, a, move |rt: &mut Runtime, b: B| {
-            (*snd).call (rt, b, next);
+            bounce (rt, move |rt: &mut Runtime| {
+                (*snd).call (rt, b, next);
+            });
         });
     }
 
+    fn describe (&self) -> Description {
+        Description::chain ("bind", self.fst.describe (), self.snd.describe ())
+    }
+
+}
+
+//  _____               
+// |  ___|   _ ___  ___ 
+// | |_ | | | / __|/ _ \
+// |  _|| |_| \__ \  __/
+// |_|   \__,_|___/\___|
+//                      
+
+pub struct Fuse<B,X,Y> {
+    mid : PhantomData<B>,
+    fst : X,
+    snd : Y,
+}
+
+pub fn fuse<A,B,C,X,Y> (x: X, y: Y) -> Fuse<B,X,Y>
+where A: Send + 'static,
+      B: Send + 'static,
+      C: Send + 'static,
+      X: Immediate<A,B>,
+      Y: Immediate<B,C>,
+{
+    Fuse {
+        mid: PhantomData,
+        fst: x,
+        snd: y,
+    }
+}
+
+impl<A,B,C,X,Y> Arrow<A,C> for Fuse<B,X,Y>
+where A: Send + 'static,
+      B: Send + 'static,
+      C: Send + 'static,
+      X: Immediate<A,B>,
+      Y: Immediate<B,C>,
+{
+
+    fn call<F> (&self, rt: &mut Runtime, a: A, next: F)
+    where F: Continuation<C> + Send {
+        next.call (rt, self.snd.apply (self.fst.apply (a)));
+    }
+
+    fn describe (&self) -> Description {
+        Description::chain ("fuse", self.fst.describe (), self.snd.describe ())
+    }
+
+}
+
+impl<A,B,C,X,Y> Immediate<A,C> for Fuse<B,X,Y>
+where A: Send + 'static,
+      B: Send + 'static,
+      C: Send + 'static,
+      X: Immediate<A,B>,
+      Y: Immediate<B,C>,
+{
+
+    fn apply (&self, a: A) -> C {
+        self.snd.apply (self.fst.apply (a))
+    }
+
 }
 
 //  _____ _       _   _             
@@ -171,5 +432,366 @@ where A: Send + 'static,
         });
     }
 
+    fn describe (&self) -> Description {
+        Description::node ("flatten", vec! [self.fst.describe ()])
+    }
+
+}
+
+//  ____                    _    _                           
+// | __ )  _____  _____  __| |  / \   _ __ _ __ _____      __
+// |  _ \ / _ \ \/ / _ \/ _` | / _ \ | '__| '__/ _ \ \ /\ / /
+// | |_) | (_) >  <  __/ (_| |/ ___ \| |  | | | (_) \ V  V / 
+// |____/ \___/_/\_\___|\__,_/_/   \_\_|  |_|  \___/ \_/\_/  
+//                                                           
+
+trait ArrowObject<A,B> : Send + Sync {
+    fn call_box (&self, rt: &mut Runtime, a: A, next: Box<Continuation<B> + Send>);
+    fn describe_box (&self) -> Description;
+}
+
+impl<A,B,X> ArrowObject<A,B> for X
+where A: Send + 'static,
+      B: Send + 'static,
+      X: Arrow<A,B> + Sync,
+{
+
+    fn call_box (&self, rt: &mut Runtime, a: A, next: Box<Continuation<B> + Send>) {
+        self.call (rt, a, move |rt: &mut Runtime, b: B| {
+            next.call_box (rt, b);
+        });
+    }
+
+    fn describe_box (&self) -> Description {
+        self.describe ()
+    }
+
+}
+
+pub struct BoxedArrow<A,B> {
+    arr: Box<ArrowObject<A,B>>,
+}
+
+pub fn boxed<A,B,X> (x: X) -> BoxedArrow<A,B>
+where A: Send + 'static,
+      B: Send + 'static,
+      X: Arrow<A,B> + Sync + 'static,
+{
+    BoxedArrow {arr: Box::new (x)}
+}
+
+impl<A,B> Arrow<A,B> for BoxedArrow<A,B>
+where A: Send + 'static,
+      B: Send + 'static,
+{
+
+    fn call<F> (&self, rt: &mut Runtime, a: A, next: F)
+    where F: Continuation<B> + Send {
+        self.arr.call_box (rt, a, Box::new (next));
+    }
+
+    fn describe (&self) -> Description {
+        self.arr.describe_box ()
+    }
+
+}
+
+//  ____  _                        _ 
+// / ___|| |__   __ _ _ __ ___  __| |
+// \___ \| '_ \ / _` | '__/ _ \/ _` |
+//  ___) | | | | (_| | | |  __/ (_| |
+// |____/|_| |_|\__,_|_|  \___|\__,_|
+//                                   
+
+// A cheaply clonable handle on a single arrow instance. `Arrow` cannot be
+// implemented for `Arc<X>` directly as it would overlap with closures.
+
+pub struct Shared<X> {
+    arr: Arc<X>,
+}
+
+impl<X> Clone for Shared<X> {
+
+    fn clone (&self) -> Shared<X> {
+        Shared {arr: self.arr.clone ()}
+    }
+
+}
+
+pub fn shared<A,B,X> (x: X) -> Shared<X>
+where A: Send + 'static,
+      B: Send + 'static,
+      X: Arrow<A,B> + Sync,
+{
+    Shared {arr: Arc::new (x)}
+}
+
+impl<X> From<Arc<X>> for Shared<X> {
+
+    fn from (arr: Arc<X>) -> Shared<X> {
+        Shared {arr: arr}
+    }
+
+}
+
+impl<A,B,X> Arrow<A,B> for Shared<X>
+where A: Send + 'static,
+      B: Send + 'static,
+      X: Arrow<A,B> + Sync,
+{
+
+    fn call<F> (&self, rt: &mut Runtime, a: A, next: F)
+    where F: Continuation<B> + Send {
+        self.arr.call (rt, a, next);
+    }
+
+    fn describe (&self) -> Description {
+        self.arr.describe ()
+    }
+
+}
+
+//  ____                          _           
+// |  _ \ ___  ___ _   _ _ __ ___(_)_   _____ 
+// | |_) / _ \/ __| | | | '__/ __| \ \ / / _ \
+// |  _ <  __/ (__| |_| | |  \__ \ |\ V /  __/
+// |_| \_\___|\___|\__,_|_|  |___/_| \_/ \___|
+//                                            
+
+// The definition refers to itself through handles holding the cell weakly, so
+// that it is freed with the returned arrow. A pending call holds the cell
+// until it completes, so that it can finish even if the arrow is dropped.
+
+type RecCell<A,B> = Mutex<Option<Arc<ArrowObject<A,B>>>>;
+
+enum RecRef<A,B> {
+    Owner  (Arc<RecCell<A,B>>),
+    Handle (Weak<RecCell<A,B>>),
+}
+
+pub struct Rec<A,B> {
+    cell:   RecRef<A,B>,
+    active: Arc<AtomicBool>,
+}
+
+impl<A,B> Clone for Rec<A,B> {
+
+    fn clone (&self) -> Rec<A,B> {
+        let cell = match self.cell {
+            RecRef::Owner (ref cell) => RecRef::Owner (cell.clone ()),
+            RecRef::Handle (ref cell) => RecRef::Handle (cell.clone ()),
+        };
+        Rec {cell: cell, active: self.active.clone ()}
+    }
+
+}
+
+impl<A,B> Rec<A,B> {
+
+    fn cell (&self) -> Option<Arc<RecCell<A,B>>> {
+        match self.cell {
+            RecRef::Owner (ref cell) => Option::Some (cell.clone ()),
+            RecRef::Handle (ref cell) => cell.upgrade (),
+        }
+    }
+
+}
+
+pub fn recursive<A,B,X,F> (f: F) -> Rec<A,B>
+where A: Send + 'static,
+      B: Send + 'static,
+      X: Arrow<A,B> + Sync + 'static,
+      F: FnOnce(Rec<A,B>) -> X,
+{
+    let cell = Arc::new (Mutex::new (Option::None));
+    let active = Arc::new (AtomicBool::new (false));
+    let def = f (Rec {
+        cell:   RecRef::Handle (Arc::downgrade (&cell)),
+        active: active.clone (),
+    });
+    *cell.lock ().unwrap () = Option::Some (Arc::new (def) as Arc<ArrowObject<A,B>>);
+    Rec {cell: RecRef::Owner (cell), active: active}
+}
+
+impl<A,B> Arrow<A,B> for Rec<A,B>
+where A: Send + 'static,
+      B: Send + 'static,
+{
+
+    fn call<F> (&self, rt: &mut Runtime, a: A, next: F)
+    where F: Continuation<B> + Send {
+        let cell = match self.cell () {
+            Option::Some (cell) => cell,
+            Option::None => { panic! ("recursive arrow called after being dropped"); }
+        };
+        let arr = cell.lock ().unwrap ().clone ();
+        match arr {
+            Option::Some (arr) => {
+                arr.call_box (rt, a, Box::new (move |rt: &mut Runtime, b: B| {
+                    drop (cell);
+                    next.call (rt, b);
+                }));
+            }
+            Option::None => { panic! ("recursive arrow called during its definition"); }
+        }
+    }
+
+    fn describe (&self) -> Description {
+        if self.active.swap (true, Ordering::SeqCst) {
+            return Description::new ("recursion");
+        }
+        let arr = self.cell ().and_then (|cell| { cell.lock ().unwrap ().clone () });
+        let body = arr.map (|arr| { arr.describe_box () });
+        self.active.store (false, Ordering::SeqCst);
+        Description::node ("recursive", body.into_iter ().collect ())
+    }
+
+}
+
+//  ___           _              _       
+// |_ _|_ __  ___| |_ __ _ _ __ | |_ ___ 
+//  | || '_ \/ __| __/ _` | '_ \| __/ __|
+//  | || | | \__ \ || (_| | | | | |_\__ \
+// |___|_| |_|___/\__\__,_|_| |_|\__|___/
+//                                       
+
+// Each call to `next` runs one instant and returns the values emitted on the
+// output during it. The iteration stops once the process has nothing left to
+// do.
+
+pub struct Instants<V> {
+    rt:      SeqRuntime,
+    out:     Output<V>,
+    running: bool,
+}
+
+impl<V> Iterator for Instants<V>
+where V: Send + 'static
+{
+    type Item = Vec<V>;
+
+    fn next (&mut self) -> Option<Vec<V>> {
+        if ! self.running { return Option::None; }
+        self.running = self.rt.instant ();
+        Option::Some (self.out.take ())
+    }
+
+}
+
+//  ____                      _       _   _             
+// |  _ \  ___  ___  ___ _ __(_)_ __ | |_(_) ___  _ __  
+// | | | |/ _ \/ __|/ __| '__| | '_ \| __| |/ _ \| '_ \ 
+// | |_| |  __/\__ \ (__| |  | | |_) | |_| | (_) | | | |
+// |____/ \___||___/\___|_|  |_| .__/ \__|_|\___/|_| |_|
+//                             |_|                      
+
+// The structure of a process, as given by `Arrow::describe`. Signals are
+// identified by the address of their shared state, and numbered by order of
+// appearance when printed.
+
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub struct Description {
+    pub name     : String,
+    pub label    : Option<String>,
+    pub signals  : Vec<usize>,
+    pub children : Vec<Description>,
+}
+
+impl Description {
+
+    pub fn new (name: &str) -> Description {
+        Description::node (name, Vec::new ())
+    }
+
+    pub fn node (name: &str, children: Vec<Description>) -> Description {
+        Description::signal (name, Vec::new (), children)
+    }
+
+    pub fn signal (name: &str, signals: Vec<usize>, children: Vec<Description>) -> Description {
+        Description {
+            name     : name.to_string (),
+            label    : Option::None,
+            signals  : signals,
+            children : children,
+        }
+    }
+
+    // Nested sequences are flattened into a single node.
+    pub fn chain (name: &str, fst: Description, snd: Description) -> Description {
+        let mut children = Vec::new ();
+        for d in [fst, snd] {
+            if d.name == name && d.label.is_none () {
+                children.extend (d.children);
+            } else {
+                children.push (d);
+            }
+        }
+        Description::node (name, children)
+    }
+
+    fn text (&self) -> String {
+        match self.label {
+            Option::Some (ref label) => format! ("{}: {}", label, self.name),
+            Option::None => self.name.clone (),
+        }
+    }
+
+    fn write (&self, f: &mut fmt::Formatter, depth: usize, signals: &mut Vec<usize>) -> fmt::Result {
+        write! (f, "{:1$}{2}", "", depth * 2, self.text ())?;
+        for &id in self.signals.iter () {
+            write! (f, " s{}", signal_number (signals, id))?;
+        }
+        writeln! (f)?;
+        for child in self.children.iter () {
+            child.write (f, depth + 1, signals)?;
+        }
+        Result::Ok (())
+    }
+
+    pub fn to_dot (&self) -> String {
+        let mut out = String::from ("digraph process {\n");
+        let mut signals = Vec::new ();
+        self.dot (&mut out, &mut 0, &mut signals);
+        for i in 0..signals.len () {
+            out.push_str (&format! ("    s{0} [label=\"s{0}\", shape=diamond];\n", i));
+        }
+        out.push_str ("}\n");
+        out
+    }
+
+    fn dot (&self, out: &mut String, count: &mut usize, signals: &mut Vec<usize>) -> usize {
+        let id = *count;
+        *count = *count + 1;
+        out.push_str (&format! ("    n{} [label=\"{}\"];\n", id, self.text ().replace ("\"", "\\\"")));
+        for &signal in self.signals.iter () {
+            let s = signal_number (signals, signal);
+            if self.name == "emit" {
+                out.push_str (&format! ("    n{} -> s{} [style=dashed];\n", id, s));
+            } else {
+                out.push_str (&format! ("    s{} -> n{} [style=dashed];\n", s, id));
+            }
+        }
+        for child in self.children.iter () {
+            let c = child.dot (out, count, signals);
+            out.push_str (&format! ("    n{} -> n{};\n", id, c));
+        }
+        id
+    }
+
+}
+
+fn signal_number (signals: &mut Vec<usize>, id: usize) -> usize {
+    match signals.iter ().position (|&s| s == id) {
+        Option::Some (i) => i,
+        Option::None => { signals.push (id); signals.len () - 1 }
+    }
+}
+
+impl fmt::Display for Description {
+
+    fn fmt (&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.write (f, 0, &mut Vec::new ())
+    }
+
 }
 
@@ -5,10 +5,11 @@ use std::clone::{Clone};
 use std::cell::{RefCell};
 use std::sync::{Arc,Mutex};
 use std::mem::{swap};
+use std::collections::{VecDeque};
 use std::marker::{PhantomData};
 
-use runtime::{Runtime,Continuation};
-use arrow::{Arrow};
+use runtime::{Runtime,Continuation,Token,with_token,guard,is_alive,park};
+use arrow::{Arrow,Immediate,Description};
 
 //  ___    _            _   _ _         
 // |_ _|__| | ___ _ __ | |_(_) |_ _   _ 
@@ -23,6 +24,28 @@ pub fn identity () -> Identity {
     Identity ()
 }
 
+// The identity is elided from the compositions.
+
+impl Identity {
+
+    pub fn bind<A,C,Y> (self, y: Y) -> Y
+    where A: Send + 'static,
+          C: Send + 'static,
+          Y: Arrow<A,C>,
+    {
+        y
+    }
+
+    pub fn fuse<A,C,Y> (self, y: Y) -> Y
+    where A: Send + 'static,
+          C: Send + 'static,
+          Y: Immediate<A,C>,
+    {
+        y
+    }
+
+}
+
 impl<A> Arrow<A,A> for Identity
 where A: Send + 'static
 {
@@ -32,6 +55,20 @@ where A: Send + 'static
         next.call (rt, a);
     }
 
+    fn describe (&self) -> Description {
+        Description::new ("identity")
+    }
+
+}
+
+impl<A> Immediate<A,A> for Identity
+where A: Send + 'static
+{
+
+    fn apply (&self, a: A) -> A {
+        a
+    }
+
 }
 
 // __     __    _            
@@ -55,6 +92,22 @@ where B: Clone + Send + 'static
     }
 }
 
+// The function is applied once, when the arrow is built.
+
+impl<A,B> Value<A,B>
+where A: Send + 'static,
+      B: Clone + Send + 'static,
+{
+
+    pub fn map<C,F> (self, f: F) -> Value<A,C>
+    where C: Clone + Send + 'static,
+          F: FnOnce(B) -> C,
+    {
+        value (f (self.val))
+    }
+
+}
+
 impl<A,B> Arrow<A,B> for Value<A,B>
 where A: Send + 'static,
       B: Clone + Send + 'static
@@ -65,6 +118,21 @@ where A: Send + 'static,
         next.call (rt, self.val.clone ());
     }
 
+    fn describe (&self) -> Description {
+        Description::new ("value")
+    }
+
+}
+
+impl<A,B> Immediate<A,B> for Value<A,B>
+where A: Send + 'static,
+      B: Clone + Send + 'static
+{
+
+    fn apply (&self, _: A) -> B {
+        self.val.clone ()
+    }
+
 }
 
 //  __  __             
@@ -84,6 +152,23 @@ where F: Fn(A) -> B + Send + 'static
     Map {f: f}
 }
 
+// Two successive maps are composed into a single one.
+
+impl<F> Map<F> {
+
+    pub fn map<A,B,C,G> (self, g: G) -> Map<impl Fn(A) -> C + Send + 'static>
+    where A: Send + 'static,
+          B: Send + 'static,
+          C: Send + 'static,
+          F: Fn(A) -> B + Send + 'static,
+          G: Fn(B) -> C + Send + 'static,
+    {
+        let f = self.f;
+        map (move |a: A| { g (f (a)) })
+    }
+
+}
+
 impl<A,B,F> Arrow<A,B> for Map<F>
 where A: Send + 'static,
       B: Send + 'static,
@@ -95,6 +180,161 @@ where A: Send + 'static,
         next.call (rt, (self.f) (a));
     }
 
+    fn describe (&self) -> Description {
+        Description::new ("map")
+    }
+
+}
+
+impl<A,B,F> Immediate<A,B> for Map<F>
+where A: Send + 'static,
+      B: Send + 'static,
+      F: Fn(A) -> B + Send + 'static
+{
+
+    fn apply (&self, a: A) -> B {
+        (self.f) (a)
+    }
+
+}
+
+//  ____  _        _        __       _ 
+// / ___|| |_ __ _| |_ ___ / _|_   _| |
+// \___ \| __/ _` | __/ _ \ |_| | | | |
+//  ___) | || (_| | ||  __/  _| |_| | |
+// |____/ \__\__,_|\__\___|_|  \__,_|_|
+//                                     
+
+// The state belongs to the arrow: it persists across its activations,
+// including the iterations of a fixpoint.
+
+pub struct Stateful<S,F> {
+    state: Mutex<S>,
+    f: F,
+}
+
+pub fn stateful<S,A,B,F> (init: S, f: F) -> Stateful<S,F>
+where S: Send + 'static,
+      F: Fn(&mut S, A) -> B + Send + 'static
+{
+    Stateful {state: Mutex::new (init), f: f}
+}
+
+impl<S,A,B,F> Arrow<A,B> for Stateful<S,F>
+where S: Send + 'static,
+      A: Send + 'static,
+      B: Send + 'static,
+      F: Fn(&mut S, A) -> B + Send + 'static
+{
+
+    fn call<C> (&self, rt: &mut Runtime, a: A, next: C)
+    where C: Continuation<B> + Send {
+        next.call (rt, self.apply (a));
+    }
+
+    fn describe (&self) -> Description {
+        Description::new ("stateful")
+    }
+
+}
+
+impl<S,A,B,F> Immediate<A,B> for Stateful<S,F>
+where S: Send + 'static,
+      A: Send + 'static,
+      B: Send + 'static,
+      F: Fn(&mut S, A) -> B + Send + 'static
+{
+
+    fn apply (&self, a: A) -> B {
+        let mut state = self.state.lock ().unwrap ();
+        (self.f) (&mut *state, a)
+    }
+
+}
+
+//  ____                  
+// / ___|  ___ __ _ _ __  
+// \___ \ / __/ _` | '_ \ 
+//  ___) | (_| (_| | | | |
+// |____/ \___\__,_|_| |_|
+//                        
+
+pub struct Scan<S,F> {
+    state: Mutex<S>,
+    f: F,
+}
+
+pub fn scan<S,A,F> (init: S, f: F) -> Scan<S,F>
+where S: Clone + Send + 'static,
+      F: Fn(S, A) -> S + Send + 'static
+{
+    Scan {state: Mutex::new (init), f: f}
+}
+
+impl<S,A,F> Arrow<A,S> for Scan<S,F>
+where S: Clone + Send + 'static,
+      A: Send + 'static,
+      F: Fn(S, A) -> S + Send + 'static
+{
+
+    fn call<C> (&self, rt: &mut Runtime, a: A, next: C)
+    where C: Continuation<S> + Send {
+        next.call (rt, self.apply (a));
+    }
+
+    fn describe (&self) -> Description {
+        Description::new ("scan")
+    }
+
+}
+
+impl<S,A,F> Immediate<A,S> for Scan<S,F>
+where S: Clone + Send + 'static,
+      A: Send + 'static,
+      F: Fn(S, A) -> S + Send + 'static
+{
+
+    fn apply (&self, a: A) -> S {
+        let mut state = self.state.lock ().unwrap ();
+        let s = (self.f) (state.clone (), a);
+        *state = s.clone ();
+        s
+    }
+
+}
+
+//  ____       _           _ _   _           
+// |  _ \ _ __(_)_ __ ___ (_) |_(_)_   _____ 
+// | |_) | '__| | '_ ` _ \| | __| \ \ / / _ \
+// |  __/| |  | | | | | | | | |_| |\ V /  __/
+// |_|   |_|  |_|_| |_| |_|_|\__|_| \_/ \___|
+//                                           
+
+pub struct Primitive<F> {
+    f: F,
+}
+
+pub fn primitive<A,B,F> (f: F) -> Primitive<F>
+where F: Fn(&mut Runtime, A, Box<Continuation<B> + Send>) + Send + 'static
+{
+    Primitive {f: f}
+}
+
+impl<A,B,F> Arrow<A,B> for Primitive<F>
+where A: Send + 'static,
+      B: Send + 'static,
+      F: Fn(&mut Runtime, A, Box<Continuation<B> + Send>) + Send + 'static
+{
+
+    fn call<C> (&self, rt: &mut Runtime, a: A, next: C)
+    where C: Continuation<B> + Send {
+        (self.f) (rt, a, Box::new (next));
+    }
+
+    fn describe (&self) -> Description {
+        Description::new ("primitive")
+    }
+
 }
 
 //  ____                      
@@ -126,6 +366,10 @@ where A: Send + 'static
         }));
     }
 
+    fn describe (&self) -> Description {
+        Description::new ("pause")
+    }
+
 }
 
 //  _____ _                  _       _   
@@ -146,19 +390,56 @@ where A: Send + 'static,
     Fixpoint {arr: Arc::new(x)}
 }
 
+// Iterations completing in the same call are run by a loop instead of a
+// recursive call, so an instantaneous loop does not grow the stack.
+
+struct Trampoline<A,F> {
+    looping : bool,
+    pending : Option<A>,
+    next    : Option<F>,
+}
+
 fn fixpoint_rec<A,B,X,F> (arr: Arc<X>, rt: &mut Runtime, a: A, next: F)
 where A: Send + 'static,
       B: Send + 'static,
       X: Arrow<A,Result<A,B>> + Sync + 'static,
       F: Continuation<B> + Send
 {
-    let rec = arr.clone ();
-    (*arr).call (rt, a, move |rt: &mut Runtime, r: Result<A,B>| {
-        match r {
-            Result::Ok(a)  => { fixpoint_rec (rec, rt, a, next); }
-            Result::Err(b) => { next.call (rt, b); }
-        }
-    });
+    let state = Arc::new (Mutex::new (Trampoline {
+        looping: true,
+        pending: Option::Some (a),
+        next: Option::Some (next),
+    }));
+    loop {
+        let a = {
+            let mut st = state.lock ().unwrap ();
+            match st.pending.take () {
+                Option::Some (a) => a,
+                Option::None => { st.looping = false; return; }
+            }
+        };
+        let st = state.clone ();
+        let rec = arr.clone ();
+        (*arr).call (rt, a, move |rt: &mut Runtime, r: Result<A,B>| {
+            let mut st = st.lock ().unwrap ();
+            match r {
+                Result::Ok(a) => {
+                    if st.looping {
+                        st.pending = Option::Some (a);
+                    } else {
+                        let next = st.next.take ().unwrap ();
+                        drop (st);
+                        fixpoint_rec (rec, rt, a, next);
+                    }
+                }
+                Result::Err(b) => {
+                    let next = st.next.take ().unwrap ();
+                    drop (st);
+                    next.call (rt, b);
+                }
+            }
+        });
+    }
 }
 
 impl<A,B,X> Arrow<A,B> for Fixpoint<X>
@@ -172,6 +453,221 @@ where A: Send + 'static,
         fixpoint_rec (self.arr.clone (), rt, a, next);
     }
 
+    fn describe (&self) -> Description {
+        Description::node ("fixpoint", vec! [self.arr.describe ()])
+    }
+
+}
+
+//     _              _ _____ _                
+//    / \   _ __   __| |_   _| |__   ___ _ __  
+//   / _ \ | '_ \ / _` | | | | '_ \ / _ \ '_ \ 
+//  / ___ \| | | | (_| | | | | | | |  __/ | | |
+// /_/   \_\_| |_|\__,_| |_| |_| |_|\___|_| |_|
+//                                             
+
+pub struct AndThen<B,X,Y> {
+    mid : PhantomData<B>,
+    fst : X,
+    snd : Arc<Y>,
+}
+
+pub fn and_then<A,B,C,E,X,Y> (x: X, y: Y) -> AndThen<B,X,Y>
+where A: Send + 'static,
+      B: Send + 'static,
+      C: Send + 'static,
+      E: Send + 'static,
+      X: Arrow<A,Result<B,E>> + 'static,
+      Y: Arrow<B,Result<C,E>> + 'static,
+{
+    AndThen {
+        mid: PhantomData,
+        fst: x,
+        snd: Arc::new (y),
+    }
+}
+
+impl<A,B,C,E,X,Y> Arrow<A,Result<C,E>> for AndThen<B,X,Y>
+where A: Send + 'static,
+      B: Send + 'static,
+      C: Send + 'static,
+      E: Send + 'static,
+      X: Arrow<A,Result<B,E>> + Send + 'static,
+      Y: Arrow<B,Result<C,E>> + Send + Sync + 'static,
+{
+
+    fn call<F> (&self, rt: &mut Runtime, a: A, next: F)
+    where F: Continuation<Result<C,E>> + Send {
+        let snd = self.snd.clone ();
+        self.fst.call (rt, a, move |rt: &mut Runtime, r: Result<B,E>| {
+            match r {
+                Result::Ok (b)  => { (*snd).call (rt, b, next); }
+                Result::Err (e) => { next.call (rt, Result::Err (e)); }
+            }
+        });
+    }
+
+    fn describe (&self) -> Description {
+        Description::node ("and_then", vec! [self.fst.describe (), self.snd.describe ()])
+    }
+
+}
+
+//  __  __             _____           
+// |  \/  | __ _ _ __ | ____|_ __ _ __ 
+// | |\/| |/ _` | '_ \|  _| | '__| '__|
+// | |  | | (_| | |_) | |___| |  | |   
+// |_|  |_|\__,_| .__/|_____|_|  |_|   
+//              |_|                    
+
+pub struct MapErr<E,X,G> {
+    err : PhantomData<E>,
+    arr : X,
+    f   : Arc<G>,
+}
+
+pub fn map_err<A,B,E,D,X,G> (x: X, f: G) -> MapErr<E,X,G>
+where A: Send + 'static,
+      B: Send + 'static,
+      E: Send + 'static,
+      D: Send + 'static,
+      X: Arrow<A,Result<B,E>> + 'static,
+      G: Fn(E) -> D + Send + Sync + 'static,
+{
+    MapErr {
+        err: PhantomData,
+        arr: x,
+        f:   Arc::new (f),
+    }
+}
+
+impl<A,B,E,D,X,G> Arrow<A,Result<B,D>> for MapErr<E,X,G>
+where A: Send + 'static,
+      B: Send + 'static,
+      E: Send + 'static,
+      D: Send + 'static,
+      X: Arrow<A,Result<B,E>> + Send + 'static,
+      G: Fn(E) -> D + Send + Sync + 'static,
+{
+
+    fn call<F> (&self, rt: &mut Runtime, a: A, next: F)
+    where F: Continuation<Result<B,D>> + Send {
+        let f = self.f.clone ();
+        self.arr.call (rt, a, move |rt: &mut Runtime, r: Result<B,E>| {
+            next.call (rt, r.map_err (|e| (*f) (e)));
+        });
+    }
+
+    fn describe (&self) -> Description {
+        Description::node ("map_err", vec! [self.arr.describe ()])
+    }
+
+}
+
+//   ___       _____ _          
+//  / _ \ _ __| ____| |___  ___ 
+// | | | | '__|  _| | / __|/ _ \
+// | |_| | |  | |___| \__ \  __/
+//  \___/|_|  |_____|_|___/\___|
+//                              
+
+pub struct OrElse<E,X,Y> {
+    err : PhantomData<E>,
+    arr : X,
+    hdl : Arc<Y>,
+}
+
+pub fn or_else<A,B,E,D,X,Y> (x: X, y: Y) -> OrElse<E,X,Y>
+where A: Send + 'static,
+      B: Send + 'static,
+      E: Send + 'static,
+      D: Send + 'static,
+      X: Arrow<A,Result<B,E>> + 'static,
+      Y: Arrow<E,Result<B,D>> + 'static,
+{
+    OrElse {
+        err: PhantomData,
+        arr: x,
+        hdl: Arc::new (y),
+    }
+}
+
+impl<A,B,E,D,X,Y> Arrow<A,Result<B,D>> for OrElse<E,X,Y>
+where A: Send + 'static,
+      B: Send + 'static,
+      E: Send + 'static,
+      D: Send + 'static,
+      X: Arrow<A,Result<B,E>> + Send + 'static,
+      Y: Arrow<E,Result<B,D>> + Send + Sync + 'static,
+{
+
+    fn call<F> (&self, rt: &mut Runtime, a: A, next: F)
+    where F: Continuation<Result<B,D>> + Send {
+        let hdl = self.hdl.clone ();
+        self.arr.call (rt, a, move |rt: &mut Runtime, r: Result<B,E>| {
+            match r {
+                Result::Ok (b)  => { next.call (rt, Result::Ok (b)); }
+                Result::Err (e) => { (*hdl).call (rt, e, next); }
+            }
+        });
+    }
+
+    fn describe (&self) -> Description {
+        Description::node ("or_else", vec! [self.arr.describe (), self.hdl.describe ()])
+    }
+
+}
+
+//   ____      _       _     
+//  / ___|__ _| |_ ___| |__  
+// | |   / _` | __/ __| '_ \ 
+// | |__| (_| | || (__| | | |
+//  \____\__,_|\__\___|_| |_|
+//                           
+
+pub struct Catch<E,X,Y> {
+    err : PhantomData<E>,
+    arr : X,
+    hdl : Arc<Y>,
+}
+
+pub fn catch<A,B,E,X,Y> (x: X, y: Y) -> Catch<E,X,Y>
+where A: Send + 'static,
+      B: Send + 'static,
+      E: Send + 'static,
+      X: Arrow<A,Result<B,E>> + 'static,
+      Y: Arrow<E,B> + 'static,
+{
+    Catch {
+        err: PhantomData,
+        arr: x,
+        hdl: Arc::new (y),
+    }
+}
+
+impl<A,B,E,X,Y> Arrow<A,B> for Catch<E,X,Y>
+where A: Send + 'static,
+      B: Send + 'static,
+      E: Send + 'static,
+      X: Arrow<A,Result<B,E>> + Send + 'static,
+      Y: Arrow<E,B> + Send + Sync + 'static,
+{
+
+    fn call<F> (&self, rt: &mut Runtime, a: A, next: F)
+    where F: Continuation<B> + Send {
+        let hdl = self.hdl.clone ();
+        self.arr.call (rt, a, move |rt: &mut Runtime, r: Result<B,E>| {
+            match r {
+                Result::Ok (b)  => { next.call (rt, b); }
+                Result::Err (e) => { (*hdl).call (rt, e, next); }
+            }
+        });
+    }
+
+    fn describe (&self) -> Description {
+        Description::node ("catch", vec! [self.arr.describe (), self.hdl.describe ()])
+    }
+
 }
 
 //  ____             ____                _ 
@@ -220,6 +716,10 @@ where A: Send + 'static,
         });
     }
 
+    fn describe (&self) -> Description {
+        Description::node ("seqprod", vec! [self.fst.describe (), self.snd.describe ()])
+    }
+
 }
 
 //  ____                _            _   
@@ -315,40 +815,1120 @@ where A: Send + 'static,
         }));
     }
 
+    fn describe (&self) -> Description {
+        Description::node ("product", vec! [self.fst.describe (), self.snd.describe ()])
+    }
+
 }
 
-//  _____          _    
-// |  ___|__  _ __| | __
-// | |_ / _ \| '__| |/ /
-// |  _| (_) | |  |   < 
-// |_|  \___/|_|  |_|\_\
-//                      
+//  _____ _          _   
+// |  ___(_)_ __ ___| |_ 
+// | |_  | | '__/ __| __|
+// |  _| | | |  \__ \ |_ 
+// |_|   |_|_|  |___/\__|
+//                       
 
-pub struct Fork<X> {
-   arr: Arc<X>,
+pub struct First<X> {
+    arr: X,
 }
 
-pub fn fork<A,X> (x: X) -> Fork<X>
+pub fn first<A,B,X> (x: X) -> First<X>
 where A: Send + 'static,
-      X: Arrow<A,()> + 'static,
+      B: Send + 'static,
+      X: Arrow<A,B> + 'static,
 {
-    Fork {arr: Arc::new (x)}
+    First {arr: x}
 }
 
-impl<A,X> Arrow<A,A> for Fork<X>
-where A: Clone + Send + 'static,
-      X: Arrow<A,()> + Send + Sync + 'static,
+impl<A,B,C,X> Arrow<(A,C),(B,C)> for First<X>
+where A: Send + 'static,
+      B: Send + 'static,
+      C: Send + 'static,
+      X: Arrow<A,B> + Send + 'static,
 {
 
-    fn call<F> (&self, rt: &mut Runtime, a: A, next: F)
-    where F: Continuation<A> + Send {
-        let arr = self.arr.clone ();
-        let val = a.clone ();
-        rt.on_current_instant (Box::new (move |rt: &mut Runtime, ()| {
-            arr.call (rt, val, |_: &mut Runtime, ()| {});
-        }));
+    fn call<F> (&self, rt: &mut Runtime, (a,c): (A,C), next: F)
+    where F: Continuation<(B,C)> + Send {
+        self.arr.call (rt, a, move |rt: &mut Runtime, b: B| {
+            next.call (rt, (b,c));
+        });
+    }
+
+    fn describe (&self) -> Description {
+        Description::node ("first", vec! [self.arr.describe ()])
+    }
+
+}
+
+//  ____                           _ 
+// / ___|  ___  ___ ___  _ __   __| |
+// \___ \ / _ \/ __/ _ \| '_ \ / _` |
+//  ___) |  __/ (_| (_) | | | | (_| |
+// |____/ \___|\___\___/|_| |_|\__,_|
+//                                   
+
+pub struct Second<X> {
+    arr: X,
+}
+
+pub fn second<A,B,X> (x: X) -> Second<X>
+where A: Send + 'static,
+      B: Send + 'static,
+      X: Arrow<A,B> + 'static,
+{
+    Second {arr: x}
+}
+
+impl<A,B,C,X> Arrow<(C,A),(C,B)> for Second<X>
+where A: Send + 'static,
+      B: Send + 'static,
+      C: Send + 'static,
+      X: Arrow<A,B> + Send + 'static,
+{
+
+    fn call<F> (&self, rt: &mut Runtime, (c,a): (C,A), next: F)
+    where F: Continuation<(C,B)> + Send {
+        self.arr.call (rt, a, move |rt: &mut Runtime, b: B| {
+            next.call (rt, (c,b));
+        });
+    }
+
+    fn describe (&self) -> Description {
+        Description::node ("second", vec! [self.arr.describe ()])
+    }
+
+}
+
+//  _____                       _   
+// |  ___|_ _ _ __   ___  _   _| |_ 
+// | |_ / _` | '_ \ / _ \| | | | __|
+// |  _| (_| | | | | (_) | |_| | |_ 
+// |_|  \__,_|_| |_|\___/ \__,_|\__|
+//                                  
+
+pub struct Fanout<X,Y> {
+    prod: SeqProd<X,Y>,
+}
+
+pub fn fanout<A,B,C,X,Y> (x: X, y: Y) -> Fanout<X,Y>
+where A: Clone + Send + 'static,
+      B: Send + 'static,
+      C: Send + 'static,
+      X: Arrow<A,B> + 'static,
+      Y: Arrow<A,C> + 'static,
+{
+    Fanout {prod: seqprod (x, y)}
+}
+
+impl<A,B,C,X,Y> Arrow<A,(B,C)> for Fanout<X,Y>
+where A: Clone + Send + 'static,
+      B: Send + 'static,
+      C: Send + 'static,
+      X: Arrow<A,B> + Send + Sync + 'static,
+      Y: Arrow<A,C> + Send + Sync + 'static,
+{
+
+    fn call<F> (&self, rt: &mut Runtime, a: A, next: F)
+    where F: Continuation<(B,C)> + Send {
+        self.prod.call (rt, (a.clone (), a), next);
+    }
+
+    fn describe (&self) -> Description {
+        Description::node ("fanout", vec! [self.prod.fst.describe (), self.prod.snd.describe ()])
+    }
+
+}
+
+//  ____            _____                       _   
+// |  _ \ __ _ _ __|  ___|_ _ _ __   ___  _   _| |_ 
+// | |_) / _` | '__| |_ / _` | '_ \ / _ \| | | | __|
+// |  __/ (_| | |  |  _| (_| | | | | (_) | |_| | |_ 
+// |_|   \__,_|_|  |_|  \__,_|_| |_|\___/ \__,_|\__|
+//                                                  
+
+pub struct ParFanout<X,Y> {
+    prod: Product<X,Y>,
+}
+
+pub fn par_fanout<A,B,C,X,Y> (x: X, y: Y) -> ParFanout<X,Y>
+where A: Clone + Send + 'static,
+      B: Send + 'static,
+      C: Send + 'static,
+      X: Arrow<A,B> + 'static,
+      Y: Arrow<A,C> + 'static,
+{
+    ParFanout {prod: product (x, y)}
+}
+
+impl<A,B,C,X,Y> Arrow<A,(B,C)> for ParFanout<X,Y>
+where A: Clone + Send + 'static,
+      B: Send + 'static,
+      C: Send + 'static,
+      X: Arrow<A,B> + Send + Sync + 'static,
+      Y: Arrow<A,C> + Send + Sync + 'static,
+{
+
+    fn call<F> (&self, rt: &mut Runtime, a: A, next: F)
+    where F: Continuation<(B,C)> + Send {
+        self.prod.call (rt, (a.clone (), a), next);
+    }
+
+    fn describe (&self) -> Description {
+        Description::node ("par_fanout", vec! [self.prod.fst.describe (), self.prod.snd.describe ()])
+    }
+
+}
+
+//  ____       _           _   
+// / ___|  ___| | ___  ___| |_ 
+// \___ \ / _ \ |/ _ \/ __| __|
+//  ___) |  __/ |  __/ (__| |_ 
+// |____/ \___|_|\___|\___|\__|
+//                             
+
+pub enum Either<A,B> {
+    Left  (A),
+    Right (B),
+}
+
+pub struct Select<X,Y> {
+    fst: Arc<X>,
+    snd: Arc<Y>,
+}
+
+pub fn select<A,B,C,D,X,Y> (x: X, y: Y) -> Select<X,Y>
+where A: Send + 'static,
+      B: Send + 'static,
+      C: Send + 'static,
+      D: Send + 'static,
+      X: Arrow<A,B> + 'static,
+      Y: Arrow<C,D> + 'static,
+{
+    Select {
+        fst: Arc::new (x),
+        snd: Arc::new (y),
+    }
+}
+
+impl<A,B,C,D,X,Y> Arrow<(A,B),Either<C,D>> for Select<X,Y>
+where A: Send + 'static,
+      B: Send + 'static,
+      C: Send + 'static,
+      D: Send + 'static,
+      X: Arrow<A,C> + Send + Sync + 'static,
+      Y: Arrow<B,D> + Send + Sync + 'static,
+{
+
+    fn call<F> (&self, rt: &mut Runtime, (a,b): (A,B), next: F)
+    where F: Continuation<Either<C,D>> + Send {
+        let parent_a = rt.token ();
+        let parent_b = parent_a.clone ();
+        let token_a = Token::new (parent_a.clone ());
+        let token_b = Token::new (parent_a.clone ());
+        let loser_a = token_b.clone ();
+        let loser_b = token_a.clone ();
+        let next_a = Arc::new (Mutex::new (Option::Some (next)));
+        let next_b = next_a.clone ();
+        let fst = self.fst.clone ();
+        let snd = self.snd.clone ();
+        with_token (rt, Option::Some (token_a), move |rt: &mut Runtime| {
+            rt.on_current_instant (Box::new (move |rt: &mut Runtime, ()| {
+                (*fst).call (rt, a, move |rt: &mut Runtime, c: C| {
+                    let next = next_a.lock ().unwrap ().take ();
+                    if let Option::Some (next) = next {
+                        loser_a.cancel (rt);
+                        with_token (rt, parent_a, move |rt: &mut Runtime| {
+                            next.call (rt, Either::Left (c));
+                        });
+                    }
+                });
+            }));
+        });
+        with_token (rt, Option::Some (token_b), move |rt: &mut Runtime| {
+            rt.on_current_instant (Box::new (move |rt: &mut Runtime, ()| {
+                (*snd).call (rt, b, move |rt: &mut Runtime, d: D| {
+                    let next = next_b.lock ().unwrap ().take ();
+                    if let Option::Some (next) = next {
+                        loser_b.cancel (rt);
+                        with_token (rt, parent_b, move |rt: &mut Runtime| {
+                            next.call (rt, Either::Right (d));
+                        });
+                    }
+                });
+            }));
+        });
+    }
+
+    fn describe (&self) -> Description {
+        Description::node ("select", vec! [self.fst.describe (), self.snd.describe ()])
+    }
+
+}
+
+//  ____             _    _ _ 
+// |  _ \ __ _ _ __ / \  | | |
+// | |_) / _` | '__/ _ \ | | |
+// |  __/ (_| | | / ___ \| | |
+// |_|   \__,_|_|/_/   \_\_|_|
+//                            
+
+pub struct ParAll<X> {
+    arrs: Vec<Arc<X>>,
+}
+
+pub fn par_all<A,B,X> (xs: Vec<X>) -> ParAll<X>
+where A: Send + 'static,
+      B: Send + 'static,
+      X: Arrow<A,B> + 'static,
+{
+    ParAll {
+        arrs: xs.into_iter ().map (Arc::new).collect (),
+    }
+}
+
+struct ParJoin<B,F> {
+    values  : Vec<Option<B>>,
+    missing : usize,
+    next    : Option<F>,
+}
+
+impl<B,F> ParJoin<B,F> {
+
+    fn new (jobs: usize, next: F) -> Arc<Mutex<ParJoin<B,F>>> {
+        Arc::new (Mutex::new (ParJoin {
+            values  : (0..jobs).map (|_| Option::None).collect (),
+            missing : jobs,
+            next    : Option::Some (next),
+        }))
+    }
+
+    // Records the value of job `i`; the last job to complete calls the
+    // continuation with the values in job order, gathered by `gather`.
+    fn complete<C,G> (join: &Mutex<ParJoin<B,F>>, rt: &mut Runtime, i: usize, b: B, gather: G)
+    where F: Continuation<C>,
+          G: FnOnce(Vec<B>) -> C,
+    {
+        let (next, values) = {
+            let mut join = join.lock ().unwrap ();
+            join.values[i] = Option::Some (b);
+            join.missing = join.missing - 1;
+            if join.missing > 0 { return; }
+            let values : Vec<B> = join.values.drain (..).map (|b| b.unwrap ()).collect ();
+            (join.next.take ().unwrap (), values)
+        };
+        next.call (rt, gather (values));
+    }
+
+}
+
+fn par_join<A,B,X,F> (rt: &mut Runtime, jobs: Vec<(Arc<X>,A)>, next: F)
+where A: Send + 'static,
+      B: Send + 'static,
+      X: Arrow<A,B> + Send + Sync + 'static,
+      F: Continuation<Vec<B>> + Send
+{
+    if jobs.is_empty () {
+        next.call (rt, Vec::new ());
+        return;
+    }
+    let join = ParJoin::new (jobs.len (), next);
+    for (i, (arr, a)) in jobs.into_iter ().enumerate () {
+        let join = join.clone ();
+        rt.on_current_instant (Box::new (move |rt: &mut Runtime, ()| {
+            (*arr).call (rt, a, move |rt: &mut Runtime, b: B| {
+                ParJoin::complete (&join, rt, i, b, |values| { values });
+            });
+        }));
+    }
+}
+
+impl<A,B,X> Arrow<Vec<A>,Vec<B>> for ParAll<X>
+where A: Send + 'static,
+      B: Send + 'static,
+      X: Arrow<A,B> + Send + Sync + 'static,
+{
+
+    fn call<F> (&self, rt: &mut Runtime, a: Vec<A>, next: F)
+    where F: Continuation<Vec<B>> + Send {
+        assert_eq! (self.arrs.len (), a.len (), "par_all: one input per arrow expected");
+        let jobs = self.arrs.iter ().cloned ().zip (a).collect ();
+        par_join (rt, jobs, next);
+    }
+
+    fn describe (&self) -> Description {
+        Description::node ("par_all", self.arrs.iter ().map (|arr| { arr.describe () }).collect ())
+    }
+
+}
+
+//  ____            __  __             
+// |  _ \ __ _ _ __|  \/  | __ _ _ __  
+// | |_) / _` | '__| |\/| |/ _` | '_ \ 
+// |  __/ (_| | |  | |  | | (_| | |_) |
+// |_|   \__,_|_|  |_|  |_|\__,_| .__/ 
+//                              |_|    
+
+pub struct ParMap<X> {
+    arr: Arc<X>,
+}
+
+pub fn par_map<A,B,X> (x: X) -> ParMap<X>
+where A: Send + 'static,
+      B: Send + 'static,
+      X: Arrow<A,B> + 'static,
+{
+    ParMap {arr: Arc::new (x)}
+}
+
+impl<A,B,X> Arrow<Vec<A>,Vec<B>> for ParMap<X>
+where A: Send + 'static,
+      B: Send + 'static,
+      X: Arrow<A,B> + Send + Sync + 'static,
+{
+
+    fn call<F> (&self, rt: &mut Runtime, a: Vec<A>, next: F)
+    where F: Continuation<Vec<B>> + Send {
+        let jobs = a.into_iter ().map (|a| (self.arr.clone (), a)).collect ();
+        par_join (rt, jobs, next);
+    }
+
+    fn describe (&self) -> Description {
+        Description::node ("par_map", vec! [self.arr.describe ()])
+    }
+
+}
+
+//  ____             ____ _                 _        __  __             
+// |  _ \ __ _ _ __ / ___| |__  _   _ _ __ | | _____|  \/  | __ _ _ __  
+// | |_) / _` | '__| |   | '_ \| | | | '_ \| |/ / __| |\/| |/ _` | '_ \ 
+// |  __/ (_| | |  | |___| | | | |_| | | | |   <\__ \ |  | | (_| | |_) |
+// |_|   \__,_|_|   \____|_| |_|\__,_|_| |_|_|\_\___/_|  |_|\__,_| .__/ 
+//                                                               |_|    
+
+// Splits the vector in as many chunks as the runtime has threads, each one
+// mapped by its own continuation, and joins them within the instant.
+
+pub struct ParChunksMap<F> {
+    f: Arc<F>,
+}
+
+pub fn par_chunks_map<A,B,F> (f: F) -> ParChunksMap<F>
+where A: Send + 'static,
+      B: Send + 'static,
+      F: Fn(A) -> B + Send + Sync + 'static,
+{
+    ParChunksMap {f: Arc::new (f)}
+}
+
+impl<A,B,F> Arrow<Vec<A>,Vec<B>> for ParChunksMap<F>
+where A: Send + 'static,
+      B: Send + 'static,
+      F: Fn(A) -> B + Send + Sync + 'static,
+{
+
+    fn call<C> (&self, rt: &mut Runtime, mut items: Vec<A>, next: C)
+    where C: Continuation<Vec<B>> + Send {
+        if items.is_empty () {
+            next.call (rt, Vec::new ());
+            return;
+        }
+        let threads = rt.threads ();
+        let size = items.len ().div_ceil (threads);
+        let mut chunks = Vec::new ();
+        while items.len () > size {
+            let rest = items.split_off (size);
+            chunks.push (items);
+            items = rest;
+        }
+        chunks.push (items);
+        let join = ParJoin::new (chunks.len (), next);
+        for (i, chunk) in chunks.into_iter ().enumerate () {
+            let join = join.clone ();
+            let f = self.f.clone ();
+            rt.on_current_instant (Box::new (move |rt: &mut Runtime, ()| {
+                let values = chunk.into_iter ().map (|a| { f (a) }).collect ();
+                ParJoin::complete (&join, rt, i, values, |chunks: Vec<Vec<B>>| {
+                    chunks.into_iter ().flatten ().collect ()
+                });
+            }));
+        }
+    }
+
+    fn describe (&self) -> Description {
+        Description::new ("par_chunks_map")
+    }
+
+}
+
+//  ____  _            _ _            
+// |  _ \(_)_ __   ___| (_)_ __   ___ 
+// | |_) | | '_ \ / _ \ | | '_ \ / _ \
+// |  __/| | |_) |  __/ | | | | |  __/
+// |_|   |_| .__/ \___|_|_|_| |_|\___|
+//         |_|                        
+
+// Feeds one item per instant to the process, so that when its stages are
+// separated by pauses (see `Arrow::pipe`) successive items are processed by
+// successive stages in the same instant. Outputs keep the order of inputs.
+
+pub struct Pipeline<X> {
+    arr: Arc<X>,
+}
+
+pub fn pipeline<A,B,X> (x: X) -> Pipeline<X>
+where A: Send + 'static,
+      B: Send + 'static,
+      X: Arrow<A,B> + 'static,
+{
+    Pipeline {arr: Arc::new (x)}
+}
+
+fn pipeline_feed<A,B,X,F> (rt: &mut Runtime, arr: Arc<X>, i: usize,
+                           mut items: ::std::vec::IntoIter<A>,
+                           join: Arc<Mutex<ParJoin<B,F>>>)
+where A: Send + 'static,
+      B: Send + 'static,
+      X: Arrow<A,B> + Send + Sync + 'static,
+      F: Continuation<Vec<B>> + Send
+{
+    let a = match items.next () {
+        Option::Some (a) => a,
+        Option::None => { return; }
+    };
+    let rest = arr.clone ();
+    let done = join.clone ();
+    rt.on_next_instant (Box::new (move |rt: &mut Runtime, ()| {
+        pipeline_feed (rt, rest, i + 1, items, done);
+    }));
+    (*arr).call (rt, a, move |rt: &mut Runtime, b: B| {
+        ParJoin::complete (&join, rt, i, b, |values| { values });
+    });
+}
+
+impl<A,B,X> Arrow<Vec<A>,Vec<B>> for Pipeline<X>
+where A: Send + 'static,
+      B: Send + 'static,
+      X: Arrow<A,B> + Send + Sync + 'static,
+{
+
+    fn call<F> (&self, rt: &mut Runtime, items: Vec<A>, next: F)
+    where F: Continuation<Vec<B>> + Send {
+        if items.is_empty () {
+            next.call (rt, Vec::new ());
+            return;
+        }
+        let join = ParJoin::new (items.len (), next);
+        pipeline_feed (rt, self.arr.clone (), 0, items.into_iter (), join);
+    }
+
+    fn describe (&self) -> Description {
+        Description::node ("pipeline", vec! [self.arr.describe ()])
+    }
+
+}
+
+//  _____          _    
+// |  ___|__  _ __| | __
+// | |_ / _ \| '__| |/ /
+// |  _| (_) | |  |   < 
+// |_|  \___/|_|  |_|\_\
+//                      
+
+pub struct Fork<X> {
+   arr: Arc<X>,
+}
+
+pub fn fork<A,X> (x: X) -> Fork<X>
+where A: Send + 'static,
+      X: Arrow<A,()> + 'static,
+{
+    Fork {arr: Arc::new (x)}
+}
+
+impl<A,X> Arrow<A,A> for Fork<X>
+where A: Clone + Send + 'static,
+      X: Arrow<A,()> + Send + Sync + 'static,
+{
+
+    fn call<F> (&self, rt: &mut Runtime, a: A, next: F)
+    where F: Continuation<A> + Send {
+        let arr = self.arr.clone ();
+        let val = a.clone ();
+        rt.on_current_instant (Box::new (move |rt: &mut Runtime, ()| {
+            arr.call (rt, val, |_: &mut Runtime, ()| {});
+        }));
+        next.call (rt, a);
+    }
+
+    fn describe (&self) -> Description {
+        Description::node ("fork", vec! [self.arr.describe ()])
+    }
+
+}
+
+//  _____          _    _   _                 _ _      
+// |  ___|__  _ __| | _| | | | __ _ _ __   __| | | ___ 
+// | |_ / _ \| '__| |/ / |_| |/ _` | '_ \ / _` | |/ _ \
+// |  _| (_) | |  |   <|  _  | (_| | | | | (_| | |  __/
+// |_|  \___/|_|  |_|\_\_| |_|\__,_|_| |_|\__,_|_|\___|
+//                                                     
+
+enum HandleState<B> {
+    Running,
+    Done (B),
+    Killed,
+}
+
+struct HandleData<B> {
+    state   : HandleState<B>,
+    waiters : VecDeque<(Option<Token>,Box<Continuation<Option<B>> + Send>)>,
+}
+
+pub struct Handle<B> {
+    token : Token,
+    data  : Arc<Mutex<HandleData<B>>>,
+}
+
+impl<B> Clone for Handle<B> {
+
+    fn clone (&self) -> Handle<B> {
+        Handle {
+            token: self.token.clone (),
+            data:  self.data.clone (),
+        }
+    }
+
+}
+
+impl<B> Handle<B>
+where B: Clone + Send + 'static
+{
+
+    fn terminate (&self, rt: &mut Runtime, state: HandleState<B>) {
+        let mut data = self.data.lock ().unwrap ();
+        if let HandleState::Running = data.state {} else { return; }
+        let result = match state {
+            HandleState::Done (ref b) => Option::Some (b.clone ()),
+            _ => Option::None,
+        };
+        data.state = state;
+        let token = rt.set_token (Option::None);
+        while let Option::Some ((_,ct)) = data.waiters.pop_front () {
+            let result = result.clone ();
+            rt.on_current_instant (Box::new (move |rt: &mut Runtime, ()| {
+                ct.call_box (rt, result);
+            }));
+        }
+        rt.set_token (token);
+    }
+
+    fn parked (&self, rt: &Runtime) -> Option<Token> {
+        park (rt, &self.data, |data: &Mutex<HandleData<B>>, rt: &mut Runtime| {
+            let mut data = data.lock ().unwrap ();
+            data.waiters.retain (|&(ref token,_)| is_alive (token, rt));
+        })
+    }
+
+}
+
+pub struct ForkHandle<X> {
+    arr: Arc<X>,
+}
+
+pub fn fork_handle<A,B,X> (x: X) -> ForkHandle<X>
+where A: Send + 'static,
+      B: Clone + Send + 'static,
+      X: Arrow<A,B> + 'static,
+{
+    ForkHandle {arr: Arc::new (x)}
+}
+
+impl<A,B,X> Arrow<A,Handle<B>> for ForkHandle<X>
+where A: Send + 'static,
+      B: Clone + Send + 'static,
+      X: Arrow<A,B> + Send + Sync + 'static,
+{
+
+    fn call<F> (&self, rt: &mut Runtime, a: A, next: F)
+    where F: Continuation<Handle<B>> + Send {
+        let arr = self.arr.clone ();
+        let handle = Handle {
+            token: Token::new (rt.token ()),
+            data:  Arc::new (Mutex::new (HandleData {
+                state   : HandleState::Running,
+                waiters : VecDeque::new (),
+            })),
+        };
+        let child = handle.clone ();
+        with_token (rt, Option::Some (handle.token.clone ()), move |rt: &mut Runtime| {
+            rt.on_current_instant (Box::new (move |rt: &mut Runtime, ()| {
+                arr.call (rt, a, move |rt: &mut Runtime, b: B| {
+                    child.terminate (rt, HandleState::Done (b));
+                });
+            }));
+        });
+        next.call (rt, handle);
+    }
+
+    fn describe (&self) -> Description {
+        Description::node ("fork_handle", vec! [self.arr.describe ()])
+    }
+
+}
+
+//      _       _       
+//     | | ___ (_)_ __  
+//  _  | |/ _ \| | '_ \ 
+// | |_| | (_) | | | | |
+//  \___/ \___/|_|_| |_|
+//                      
+
+pub struct Join<B> {
+    b: PhantomData<B>,
+}
+
+pub fn join<B> () -> Join<B>
+where B: Clone + Send + 'static {
+    Join {
+        b: PhantomData
+    }
+}
+
+impl<B> Arrow<Handle<B>,Option<B>> for Join<B>
+where B: Clone + Send + 'static
+{
+
+    fn call<F> (&self, rt: &mut Runtime, handle: Handle<B>, next: F)
+    where F: Continuation<Option<B>> + Send {
+        let result = {
+            let mut data = handle.data.lock ().unwrap ();
+            match data.state {
+                HandleState::Running => {
+                    let token = handle.parked (rt);
+                    data.waiters.push_back ((token.clone (), guard (token, Box::new (next))));
+                    return;
+                },
+                HandleState::Done (ref b) => Option::Some (b.clone ()),
+                HandleState::Killed => Option::None,
+            }
+        };
+        next.call (rt, result);
+    }
+
+    fn describe (&self) -> Description {
+        Description::new ("join")
+    }
+
+}
+
+//     _    _ _           
+//    / \  | (_)_   _____ 
+//   / _ \ | | \ \ / / _ \
+//  / ___ \| | |\ V /  __/
+// /_/   \_\_|_| \_/ \___|
+//                        
+
+pub struct Alive<B> {
+    b: PhantomData<B>,
+}
+
+pub fn alive<B> () -> Alive<B>
+where B: Clone + Send + 'static {
+    Alive {
+        b: PhantomData
+    }
+}
+
+impl<B> Arrow<Handle<B>,bool> for Alive<B>
+where B: Clone + Send + 'static
+{
+
+    fn call<F> (&self, rt: &mut Runtime, handle: Handle<B>, next: F)
+    where F: Continuation<bool> + Send {
+        let running = match handle.data.lock ().unwrap ().state {
+            HandleState::Running => !handle.token.is_cancelled (rt),
+            _ => false,
+        };
+        next.call (rt, running);
+    }
+
+    fn describe (&self) -> Description {
+        Description::new ("alive")
+    }
+
+}
+
+//  _  ___ _ _ 
+// | |/ (_) | |
+// | ' /| | | |
+// | . \| | | |
+// |_|\_\_|_|_|
+//             
+
+pub struct Kill<B> {
+    b: PhantomData<B>,
+}
+
+pub fn kill<B> () -> Kill<B>
+where B: Clone + Send + 'static {
+    Kill {
+        b: PhantomData
+    }
+}
+
+impl<B> Arrow<Handle<B>,()> for Kill<B>
+where B: Clone + Send + 'static
+{
+
+    fn call<F> (&self, rt: &mut Runtime, handle: Handle<B>, next: F)
+    where F: Continuation<()> + Send {
+        handle.token.cancel (rt);
+        let token = rt.set_token (Option::None);
+        rt.on_end_of_instant (Box::new (move |rt: &mut Runtime, ()| {
+            handle.terminate (rt, HandleState::Killed);
+        }));
+        rt.set_token (token);
+        next.call (rt, ());
+    }
+
+    fn describe (&self) -> Description {
+        Description::new ("kill")
+    }
+
+}
+
+//  ____                       
+// / ___|  ___ ___  _ __   ___ 
+// \___ \ / __/ _ \| '_ \ / _ \
+//  ___) | (_| (_) | |_) |  __/
+// |____/ \___\___/| .__/ \___|
+//                 |_|         
+
+pub struct Scope<X> {
+    arr: Arc<X>,
+}
+
+pub fn scope<A,B,X> (x: X) -> Scope<X>
+where A: Send + 'static,
+      B: Send + 'static,
+      X: Arrow<A,B> + 'static,
+{
+    Scope {arr: Arc::new (x)}
+}
+
+impl<A,B,X> Arrow<A,B> for Scope<X>
+where A: Send + 'static,
+      B: Send + 'static,
+      X: Arrow<A,B> + Send + Sync + 'static,
+{
+
+    fn call<F> (&self, rt: &mut Runtime, a: A, next: F)
+    where F: Continuation<B> + Send {
+        let parent = rt.token ();
+        let token = Token::new (parent.clone ());
+        let inner = token.clone ();
+        let arr = self.arr.clone ();
+        with_token (rt, Option::Some (token), move |rt: &mut Runtime| {
+            arr.call (rt, a, move |rt: &mut Runtime, b: B| {
+                inner.cancel (rt);
+                with_token (rt, parent, move |rt: &mut Runtime| {
+                    next.call (rt, b);
+                });
+            });
+        });
+    }
+
+    fn describe (&self) -> Description {
+        Description::node ("scope", vec! [self.arr.describe ()])
+    }
+
+}
+
+// __        ___ _   _      ____                     _ 
+// \ \      / (_) |_| |__  / ___|__ _ _ __   ___ ___| |
+//  \ \ /\ / /| | __| '_ \| |   / _` | '_ \ / __/ _ \ |
+//   \ V  V / | | |_| | | | |__| (_| | | | | (_|  __/ |
+//    \_/\_/  |_|\__|_| |_|\____\__,_|_| |_|\___\___|_|
+//                                                     
+
+pub struct WithCancel<X> {
+    token: Token,
+    arr:   Arc<X>,
+}
+
+pub fn with_cancel<A,B,X> (token: Token, x: X) -> WithCancel<X>
+where A: Send + 'static,
+      B: Send + 'static,
+      X: Arrow<A,B> + 'static,
+{
+    WithCancel {
+        token: token,
+        arr:   Arc::new (x),
+    }
+}
+
+fn cancellable<A,B,X,F> (rt: &mut Runtime, token: Token, arr: Arc<X>, a: A, next: F)
+where A: Send + 'static,
+      B: Send + 'static,
+      X: Arrow<A,B> + Send + Sync + 'static,
+      F: Continuation<Option<B>> + Send
+{
+    let parent_b = rt.token ();
+    let parent_c = parent_b.clone ();
+    let inner = Token::new (parent_b.clone ());
+    inner.attach (&token);
+    let next_b = Arc::new (Mutex::new (Option::Some (next)));
+    let next_c = next_b.clone ();
+    inner.on_cancel (&next_b, Box::new (move |rt: &mut Runtime| {
+        let next = next_c.lock ().unwrap ().take ();
+        if let Option::Some (next) = next {
+            with_token (rt, parent_c.clone (), move |rt: &mut Runtime| {
+                rt.on_current_instant (Box::new (move |rt: &mut Runtime, ()| {
+                    next.call (rt, Option::None);
+                }));
+            });
+        }
+    }));
+    with_token (rt, Option::Some (inner), move |rt: &mut Runtime| {
+        arr.call (rt, a, move |rt: &mut Runtime, b: B| {
+            let next = next_b.lock ().unwrap ().take ();
+            if let Option::Some (next) = next {
+                with_token (rt, parent_b, move |rt: &mut Runtime| {
+                    next.call (rt, Option::Some (b));
+                });
+            }
+        });
+    });
+}
+
+impl<A,B,X> Arrow<A,Option<B>> for WithCancel<X>
+where A: Send + 'static,
+      B: Send + 'static,
+      X: Arrow<A,B> + Send + Sync + 'static,
+{
+
+    fn call<F> (&self, rt: &mut Runtime, a: A, next: F)
+    where F: Continuation<Option<B>> + Send {
+        rt.watch (self.token.clone ());
+        cancellable (rt, self.token.clone (), self.arr.clone (), a, next);
+    }
+
+    fn describe (&self) -> Description {
+        Description::node ("with_cancel", vec! [self.arr.describe ()])
+    }
+
+}
+
+//   ____                     _ 
+//  / ___|__ _ _ __   ___ ___| |
+// | |   / _` | '_ \ / __/ _ \ |
+// | |__| (_| | | | | (_|  __/ |
+//  \____\__,_|_| |_|\___\___|_|
+//                              
+
+pub struct Cancel<A> {
+    token: Token,
+    a:     PhantomData<A>,
+}
+
+pub fn cancel<A> (token: Token) -> Cancel<A>
+where A: Send + 'static {
+    Cancel {
+        token: token,
+        a:     PhantomData,
+    }
+}
+
+impl<A> Arrow<A,A> for Cancel<A>
+where A: Send + 'static
+{
+
+    fn call<F> (&self, rt: &mut Runtime, a: A, next: F)
+    where F: Continuation<A> + Send {
+        self.token.cancel (rt);
         next.call (rt, a);
     }
 
+    fn describe (&self) -> Description {
+        Description::new ("cancel")
+    }
+
+}
+
+//  _____ _                            _   
+// |_   _(_)_ __ ___   ___  ___  _   _| |_ 
+//   | | | | '_ ` _ \ / _ \/ _ \| | | | __|
+//   | | | | | | | | |  __/ (_) | |_| | |_ 
+//   |_| |_|_| |_| |_|\___|\___/ \__,_|\__|
+//                                         
+
+// The process is given n instants, the current one included: if it has not
+// produced its value by the end of the last one, it is killed. With n = 0,
+// the process is not even started.
+
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub struct Timeout;
+
+pub struct WithTimeout<X> {
+    n:   u32,
+    arr: Arc<X>,
+}
+
+pub fn timeout<A,B,X> (n: u32, x: X) -> WithTimeout<X>
+where A: Send + 'static,
+      B: Send + 'static,
+      X: Arrow<A,B> + 'static,
+{
+    WithTimeout {
+        n:   n,
+        arr: Arc::new (x),
+    }
+}
+
+fn countdown (rt: &mut Runtime, n: u32, token: Token) {
+    if n == 1 {
+        token.cancel (rt);
+    } else {
+        rt.on_next_instant (Box::new (move |rt: &mut Runtime, ()| {
+            countdown (rt, n - 1, token);
+        }));
+    }
+}
+
+impl<A,B,X> Arrow<A,Result<B,Timeout>> for WithTimeout<X>
+where A: Send + 'static,
+      B: Send + 'static,
+      X: Arrow<A,B> + Send + Sync + 'static,
+{
+
+    fn call<F> (&self, rt: &mut Runtime, a: A, next: F)
+    where F: Continuation<Result<B,Timeout>> + Send {
+        if self.n == 0 {
+            return next.call (rt, Result::Err (Timeout));
+        }
+        let token = Token::new (Option::None);
+        let timer = Token::new (rt.token ());
+        let deadline = token.clone ();
+        let n = self.n;
+        with_token (rt, Option::Some (timer.clone ()), move |rt: &mut Runtime| {
+            countdown (rt, n, deadline);
+        });
+        cancellable (rt, token, self.arr.clone (), a, move |rt: &mut Runtime, b: Option<B>| {
+            timer.cancel (rt);
+            match b {
+                Option::Some (b) => { next.call (rt, Result::Ok (b)); }
+                Option::None     => { next.call (rt, Result::Err (Timeout)); }
+            }
+        });
+    }
+
+    fn describe (&self) -> Description {
+        Description::node (&format! ("timeout {}", self.n), vec! [self.arr.describe ()])
+    }
+
+}
+
+//   ___        _               _   
+//  / _ \ _   _| |_ _ __  _   _| |_ 
+// | | | | | | | __| '_ \| | | | __|
+// | |_| | |_| | |_| |_) | |_| | |_ 
+//  \___/ \__,_|\__| .__/ \__,_|\__|
+//                 |_|              
+
+// Values reported by a process to the outside world, collected instant by
+// instant by `Arrow::instants`.
+
+pub struct Output<V> {
+    values: Arc<Mutex<Vec<V>>>,
+}
+
+impl<V> Clone for Output<V> {
+
+    fn clone (&self) -> Output<V> {
+        Output {values: self.values.clone ()}
+    }
+
+}
+
+impl<V> Output<V>
+where V: Send + 'static
+{
+
+    pub fn new () -> Output<V> {
+        Output {values: Arc::new (Mutex::new (Vec::new ()))}
+    }
+
+    pub fn emit (&self) -> EmitOutput<V> {
+        EmitOutput {values: self.values.clone ()}
+    }
+
+    pub fn take (&self) -> Vec<V> {
+        let mut values = Vec::new ();
+        swap (&mut values, &mut *self.values.lock ().unwrap ());
+        values
+    }
+
+}
+
+pub struct EmitOutput<V> {
+    values: Arc<Mutex<Vec<V>>>,
+}
+
+impl<V> Arrow<V,()> for EmitOutput<V>
+where V: Send + 'static
+{
+
+    fn call<F> (&self, rt: &mut Runtime, v: V, next: F)
+    where F: Continuation<()> + Send {
+        self.values.lock ().unwrap ().push (v);
+        next.call (rt, ());
+    }
+
+    fn describe (&self) -> Description {
+        Description::new ("output")
+    }
+
+}
+
+//  _          _          _ _          _ 
+// | |    __ _| |__   ___| | | ___  __| |
+// | |   / _` | '_ \ / _ \ | |/ _ \/ _` |
+// | |__| (_| | |_) |  __/ | |  __/ (_| |
+// |_____\__,_|_.__/ \___|_|_|\___|\__,_|
+//                                       
+
+pub struct Labelled<X> {
+    label: String,
+    arr:   X,
+}
+
+pub fn labelled<A,B,X> (x: X, label: &str) -> Labelled<X>
+where A: Send + 'static,
+      B: Send + 'static,
+      X: Arrow<A,B>,
+{
+    Labelled {
+        label: label.to_string (),
+        arr:   x,
+    }
+}
+
+impl<A,B,X> Arrow<A,B> for Labelled<X>
+where A: Send + 'static,
+      B: Send + 'static,
+      X: Arrow<A,B>,
+{
+
+    fn call<F> (&self, rt: &mut Runtime, a: A, next: F)
+    where F: Continuation<B> + Send {
+        self.arr.call (rt, a, next);
+    }
+
+    fn describe (&self) -> Description {
+        let mut d = self.arr.describe ();
+        d.label = Option::Some (self.label.clone ());
+        d
+    }
+
 }
 
@@ -10,23 +10,26 @@ pub mod macros;
 mod tests {
 
     use std::result::{Result};
+    use std::sync::{Arc,Mutex};
+    use std::thread;
 
-    use arrow::{Arrow};
-    use arrow::prim::{identity,value,map,pause,fixpoint,product,fork};
+    use arrow::{Arrow,BoxedArrow,Rec,recursive,Immediate};
+    use arrow::prim::{identity,value,map,pause,fixpoint,product,fork,par_all,par_map,select,Either,seqprod,
+                      fork_handle,join,alive,kill,Handle,scope,with_cancel,cancel,primitive,scan,stateful,
+                      timeout,Timeout,Output,pipeline,par_chunks_map,Value,Map};
 
-    use signal::{Signal};
-    use signal::prim::{PureSignal,ValueSignal,UniqSignal};
+    use runtime::{Runtime,Continuation,Token};
+
+    use signal::{Signal,await_any,await_all};
+    use signal::prim::{PureSignal,ValueSignal,UniqSignal,AlreadyAwaited,Behaviour};
 
     //#[test]
     fn test_pause () {
-        product (
-            map (|()| { println!("foo"); })
+        let p = map (|()| { println!("foo"); })
             .bind (pause ())
             .bind (map (|()| { println!("bar"); }))
-        ,   map (|()| { println!("foo"); })
-            .bind (pause ())
-            .bind (map (|()| { println!("bar"); })) 
-        )
+            .shared ();
+        product (p.clone (), p)
         .execute_seq (((),()));
     }
 
@@ -149,7 +152,7 @@ mod tests {
         ).execute_seq (());
     }
 
-    //#[test]
+    #[test]
     fn test_uniq_signal () {
         let (s,await) = UniqSignal::new (Box::new (|a: u32, b: u32| -> u32 {a+b}));
         let p1 = identity ()
@@ -178,9 +181,11 @@ mod tests {
             .bind ( pause () );
             fixpoint (p)
         };
+        let received = Arc::new (Mutex::new (Vec::new ()));
+        let r = received.clone ();
         let p4 = {
             let p = await
-            .bind ( map (|i: u32| { println! ("{}", i); Result::Ok (()) }));
+            .bind ( map (move |i: Result<u32,AlreadyAwaited>| { r.lock ().unwrap ().push (i.unwrap ()); Result::Ok (()) }));
             fixpoint (p)
         };
 
@@ -190,6 +195,29 @@ mod tests {
         .bind (fork (p3))
         .bind (fork (p4))
         .execute_par (4,());
+        assert_eq! (*received.lock ().unwrap (), vec! [42, 42]);
+    }
+
+    #[test]
+    fn test_uniq_signal_consumer () {
+        let (s, await) = UniqSignal::new (Box::new (|a: u32, b: u32| -> u32 {a+b}));
+        let await = await.shared ();
+        let p = product (
+            product (
+                await.clone ()
+            ,   await
+            )
+        ,   value::<(),u32> (5).bind ( s.emit () )
+        ).shared ();
+        let results = vec! [
+            p.clone ().execute_seq ((((),()),())).0,
+            p.execute_par (2, (((),()),())).0,
+        ];
+        for (a, b) in results {
+            let mut results = vec! [a, b];
+            results.sort_by_key (|r| r.is_err ());
+            assert_eq! (results, vec! [Result::Ok (5), Result::Err (AlreadyAwaited)]);
+        }
     }
 
     //#[test]
@@ -213,5 +241,603 @@ mod tests {
         ).execute_seq(());
     }
 
+    #[test]
+    fn test_par_map () {
+        let p = par_map (
+            map (|i: u32| { i * 2 })
+            .bind ( pause () )
+            .bind ( map (|i: u32| { i + 1 }) )
+        );
+        assert_eq! (p.execute_seq (vec![1, 2, 3]), vec![3, 5, 7]);
+        let q = par_all (vec![
+            map ((|i| { i + 1 }) as fn(u32) -> u32),
+            map ((|i| { i * 10 }) as fn(u32) -> u32),
+        ]);
+        assert_eq! (q.execute_seq (vec![4, 4]), vec![5, 40]);
+        assert_eq! (par_map::<u32,u32,_> (identity ()).execute_seq (Vec::<u32>::new ()), Vec::<u32>::new ());
+    }
+
+    #[test]
+    fn test_select () {
+        let count = Arc::new (Mutex::new (0));
+        let c = count.clone ();
+        let slow = pause ()
+            .bind ( pause () )
+            .bind ( map (move |()| { *c.lock ().unwrap () += 1; 2u32 }) );
+        let fast = pause ()
+            .bind ( value::<(),u32> (1) );
+        match select (slow, fast).execute_seq (((),())) {
+            Either::Left (_)  => panic! ("the slowest branch won"),
+            Either::Right (v) => assert_eq! (v, 1),
+        }
+        assert_eq! (*count.lock ().unwrap (), 0);
+    }
+
+    #[test]
+    fn test_fork_handle () {
+        let p = fork_handle (
+            pause ()
+            .bind ( map (|i: u32| { i + 1 }) )
+        )
+        .bind ( join () );
+        assert_eq! (p.execute_seq (41), Option::Some (42));
+
+        let count = Arc::new (Mutex::new (0));
+        let p = fork_handle (looping (count.clone ()))
+        .bind ( pause () )
+        .bind ( map (|h: Handle<()>| { (h.clone (), (h.clone (), h)) }) )
+        .bind ( seqprod (alive (), seqprod (kill (), join ())) );
+        let (running, ((), result)) = p.execute_seq (());
+        assert! (running);
+        assert_eq! (result, Option::None);
+        assert_eq! (*count.lock ().unwrap (), 2);
+    }
+
+    #[test]
+    fn test_scope () {
+        let s = PureSignal::new ();
+        let witness = Arc::new (());
+        let weak = Arc::downgrade (&witness);
+        let count = Arc::new (Mutex::new (0));
+        let body = fork ( s.await_immediate ().bind (map (|_: Arc<()>| {})) )
+        .bind ( map (|_: Arc<()>| {}) )
+        .bind ( fork (looping (count.clone ())) )
+        .bind ( pause () )
+        .bind ( pause () );
+        let p = scope (body)
+        .bind ( pause () )
+        .bind ( pause () )
+        .bind ( map (move |()| { weak.upgrade ().is_none () }) );
+        assert! (p.execute_seq (witness));
+        assert_eq! (*count.lock ().unwrap (), 3);
+    }
+
+    #[test]
+    fn test_with_cancel () {
+        let s = ValueSignal::new (Box::new (|a: u32, b: u32| -> u32 {a+b}));
+        let t = Token::new (Option::None);
+        let p = product (
+            with_cancel (t.clone (), s.await ())
+        ,   pause ()
+            .bind ( pause () )
+            .bind ( cancel (t.clone ()) )
+        );
+        assert_eq! (p.execute_seq (((),())), (Option::None, ()));
+
+        let t = Token::new (Option::None);
+        let external = t.clone ();
+        let count = Arc::new (Mutex::new (0));
+        let p = product (
+            with_cancel (t, looping (count.clone ()))
+        ,   pause ()
+            .bind ( map (move |()| {
+                let external = external.clone ();
+                thread::spawn (move || { external.request (); }).join ().unwrap ();
+            }) )
+        );
+        assert_eq! (p.execute_seq (((),())), (Option::None, ()));
+        assert_eq! (*count.lock ().unwrap (), 2);
+    }
+
+    #[test]
+    fn test_fallible () {
+        let count = Arc::new (Mutex::new (0));
+        let c = count.clone ();
+        let parse = map (|s: &'static str| { s.parse::<u32> () })
+            .map_err (|_| { "not a number" });
+        let p = parse
+            .and_then ( pause ().bind (map (|i: u32| {
+                if i > 0 { Result::Ok (100 / i) } else { Result::Err ("division by zero") }
+            })) )
+            .and_then ( map (move |i: u32| { *c.lock ().unwrap () += 1; Result::Ok (i + 1) }) )
+            .or_else ( map (|e: &'static str| { Result::Err::<u32,String> (e.to_string ()) }) )
+            .catch ( map (|e: String| { e.len () as u32 }) );
+        let p = par_map (p);
+        assert_eq! (p.execute_seq (vec!["4", "0", "x"]), vec![26, 16, 12]);
+        assert_eq! (*count.lock ().unwrap (), 1);
+    }
+
+    #[test]
+    fn test_arrow_combinators () {
+        let p = map (|i: u32| { i + 1 })
+            .fanout ( pause ().bind (map (|i: u32| { i * 2 })) )
+            .bind ( map (|i: u32| { i * 10 }).first () )
+            .bind ( map (|i: u32| { i + 3 }).second () )
+            .bind ( map (|i: u32| { i.to_string () }).par_split (pause ()) );
+        assert_eq! (p.execute_seq (4), ("50".to_string (), 11));
+        let q = identity ()
+            .par_fanout ( map (|i: u32| { i * i }) )
+            .bind ( pause::<u32> ().split (map (|i: u32| { i + 1 })) );
+        assert_eq! (q.execute_seq (3u32), (3, 10));
+    }
+
+    fn looping (count: Arc<Mutex<u32>>) -> BoxedArrow<(),()> {
+        fixpoint::<(),(),_> (
+            map (move |()| { *count.lock ().unwrap () += 1; Result::Ok (()) })
+            .bind ( pause () )
+        )
+        .boxed ()
+    }
+
+    fn counter (count: Arc<Mutex<u32>>, n: u32) -> BoxedArrow<(),()> {
+        value::<(),u32> (0)
+        .bind ( fixpoint (
+            map (move |i: u32| {
+                *count.lock ().unwrap () += 1;
+                if i + 1 < n { Result::Ok (i + 1) } else { Result::Err (()) }
+            })
+            .bind ( pause () )
+        ))
+        .boxed ()
+    }
+
+    #[test]
+    fn test_boxed () {
+        let count = Arc::new (Mutex::new (0));
+        let p = fork (counter (count.clone (), 3))
+        .bind ( map (|()| { ((),()) }) )
+        .bind ( product (counter (count.clone (), 2), counter (count.clone (), 1)) )
+        .bind ( map (|_| { vec![(), ()] }) )
+        .bind ( par_all (vec![
+            counter (count.clone (), 4),
+            map (|()| {}).bind (pause ()).boxed (),
+        ]) );
+        p.execute_seq (());
+        assert_eq! (*count.lock ().unwrap (), 10);
+    }
+
+    #[test]
+    fn test_closures () {
+        let double = primitive (|rt: &mut Runtime, i: u32, next: Box<Continuation<u32> + Send>| {
+            rt.on_next_instant (Box::new (move |rt: &mut Runtime, ()| {
+                next.call_box (rt, i * 2);
+            }));
+        });
+        let p = (|i: u32| { i + 1 })
+            .bind ( double )
+            .bind ( fixpoint (|i: u32| { if i < 100 { Result::Ok (i * 3) } else { Result::Err (i) } }) )
+            .bind ( (|i: u32| { i + 1 }).par_fanout (pause ()) )
+            .bind ( |(a, b): (u32, u32)| { a + b } );
+        assert_eq! (p.execute_seq (4), 541);
+    }
+
+    #[test]
+    fn test_recursive () {
+        let witness = Arc::new (());
+        let weak = Arc::downgrade (&witness);
+        let fib = recursive (|fib: Rec<u32,u32>| {
+            let witness = witness;
+            let sum = pause ()
+                .bind ( product (fib.clone (), fib) )
+                .bind ( |(a, b): (u32, u32)| { a + b } );
+            primitive (move |rt: &mut Runtime, n: u32, next: Box<Continuation<u32> + Send>| {
+                let _ = &witness;
+                if n < 2 {
+                    next.call_box (rt, n);
+                } else {
+                    sum.call (rt, (n - 1, n - 2), move |rt: &mut Runtime, r: u32| {
+                        next.call_box (rt, r);
+                    });
+                }
+            })
+        });
+        assert_eq! (fib.execute_seq (10), 55);
+        assert! (weak.upgrade ().is_none ());
+    }
+
+    #[test]
+    fn test_fixpoint_instantaneous () {
+        let p = fixpoint (
+            map (|n: u32| { n + 1 })
+                .bind ( |n: u32| {
+                    if n < 1_000_000 { Result::Ok (n) } else { Result::Err (n) }
+                })
+        );
+        assert_eq! (p.execute_seq (0), 1_000_000);
+    }
+
+    #[test]
+    fn test_scan () {
+        let sum = scan (0, |s: u32, n: u32| { s + n });
+        let p = fixpoint (
+            pause ()
+                .bind ( sum )
+                .bind ( |s: u32| { if s < 100 { Result::Ok (s) } else { Result::Err (s) } })
+        );
+        assert_eq! (p.execute_seq (1), 128);
+        let count = stateful (Vec::new (), |seen: &mut Vec<u32>, n: u32| {
+            seen.push (n);
+            if seen.len () < 5 { Result::Ok (n * 2) } else { Result::Err (seen.clone ()) }
+        });
+        let p = fixpoint (pause ().bind ( count ));
+        assert_eq! (p.execute_seq (1), vec! [1, 2, 4, 8, 16]);
+    }
+
+    #[test]
+    fn test_timeout () {
+        let deadline = |n: u32| {
+            let s = ValueSignal::new (Box::new (|a: u32, b: u32| -> u32 {a+b}));
+            product (
+                timeout (n, s.await ())
+            ,   pause ()
+                .bind ( value::<(),u32> (42) )
+                .bind ( s.emit () )
+            )
+        };
+        assert_eq! (deadline (3).execute_seq (((),())), (Result::Ok (42), ()));
+        assert_eq! (deadline (2).execute_seq (((),())), (Result::Err (Timeout), ()));
+
+        let started = Arc::new (Mutex::new (false));
+        let s = started.clone ();
+        let p = timeout (0, map (move |()| { *s.lock ().unwrap () = true; }));
+        assert_eq! (p.execute_seq (()), Result::Err (Timeout));
+        assert! (!*started.lock ().unwrap ());
+
+        let looping = fixpoint::<(),(),_> (pause ().bind ( map (|()| { Result::Ok (()) }) ));
+        assert_eq! (timeout (5, looping).execute_par (2, ()), Result::Err (Timeout));
+    }
+
+    #[test]
+    fn test_instants () {
+        let out = Output::new ();
+        let p = fixpoint (
+            scan (0, |s: u32, ()| { s + 1 })
+            .bind ( out.emit ().fanout (identity ()) )
+            .bind ( map (|((), n): ((), u32)| { n }) )
+            .bind ( pause () )
+            .bind ( map (|n: u32| { if n < 3 { Result::Ok (()) } else { Result::Err (()) } }) )
+        );
+        let values: Vec<Vec<u32>> = p.instants ((), &out).collect ();
+        assert_eq! (values, vec! [vec! [1], vec! [2], vec! [3], vec! []]);
+    }
+
+    #[test]
+    fn test_await_any_all () {
+        let a = PureSignal::new ();
+        let b = ValueSignal::new (Box::new (|x: u32, y: u32| -> u32 {x+y}));
+        let c = PureSignal::new ();
+        let (d, _) = UniqSignal::new (Box::new (|x: u32, y: u32| -> u32 {x+y}));
+        let p = product (
+            product (
+                await_any (vec! [a.boxed (), b.boxed (), c.boxed (), d.boxed ()])
+            ,   map (|()| {}).bind ( await_all (vec! [a.boxed (), b.boxed (), c.boxed ()]) )
+            )
+        ,   pause ()
+            .bind ( value::<(),u32> (1) )
+            .bind ( b.emit () )
+            .bind ( c.emit () )
+            .bind ( pause () )
+            .bind ( pause () )
+            .bind ( a.emit () )
+        );
+        assert_eq! (p.execute_seq ((((),()),())), ((vec! [1], ()), ()));
+
+        let b = ValueSignal::new (Box::new (|x: u32, y: u32| -> u32 {x+y}));
+        let c = PureSignal::new ();
+        let p = value::<(),u32> (1)
+            .bind ( b.emit () )
+            .bind ( c.emit () )
+            .bind ( await_any (vec! [a.boxed (), b.boxed (), c.boxed ()]) );
+        assert_eq! (p.execute_seq (()), vec! [1, 2]);
+
+        let s = PureSignal::new ();
+        let clocked = primitive (|rt: &mut Runtime, present: Vec<usize>, next: Box<Continuation<(Vec<usize>,u64)> + Send>| {
+            let clock = rt.clock ();
+            next.call_box (rt, (present, clock));
+        });
+        let p = product (
+            await_any (vec! [s.boxed ()]).bind (clocked)
+        ,   pause ().bind ( s.emit () )
+        );
+        assert_eq! (p.execute_seq (((),())), ((vec! [0], 1), ()));
+        assert_eq! (await_any (vec! []).execute_seq (()), Vec::<usize>::new ());
+    }
+
+    #[test]
+    fn test_fuse () {
+        let f = map (|i: u32| { i + 1 })
+            .fuse ( identity () )
+            .fuse ( |i: u32| { i * 2 } )
+            .fuse ( scan (0, |s: u32, i: u32| { s + i }) );
+        assert_eq! (f.apply (1), 4);
+        assert_eq! (f.apply (2), 10);
+        let calls = Arc::new (Mutex::new (0));
+        let c = calls.clone ();
+        let v : Value<(),u32> = value (20).map (move |v: u32| { *c.lock ().unwrap () += 1; v + 1 });
+        let p = v
+            .bind ( f )
+            .bind ( pause () );
+        assert_eq! (p.execute_seq (()), 54);
+        assert_eq! (*calls.lock ().unwrap (), 1);
+        let v : Value<(),u32> = identity ().bind (value::<(),u32> (7));
+        assert_eq! (v.execute_seq (()), 7);
+        let g : Map<_> = identity ().fuse (map (|i: u32| { i + 1 }).map (|i: u32| { i * 2 }));
+        assert_eq! (g.apply (1), 4);
+    }
+
+    #[test]
+    fn test_describe () {
+        let s = PureSignal::new ();
+        let p = product (
+            s.emit ()
+            .bind ( pause () )
+            .bind ( pause () )
+            .label ("emitter")
+        ,   fixpoint (
+                s.await_immediate ()
+                .bind ( map (|()| { Result::Err (()) }) )
+            )
+        );
+        assert_eq! (format! ("{}", p.describe ()), "\
+product
+  emitter: bind
+    emit s0
+    pause
+    pause
+  fixpoint
+    bind
+      await immediate s0
+      map
+");
+        let dot = p.describe ().to_dot ();
+        assert! (dot.contains ("n2 -> s0 [style=dashed];"));
+        assert! (dot.contains ("s0 -> n7 [style=dashed];"));
+
+        let r = recursive (|r: Rec<u32,u32>| { pause ().bind (r) });
+        assert_eq! (format! ("{}", r.describe ()), "recursive\n  bind\n    pause\n    recursion\n");
+    }
+
+    #[test]
+    fn test_pipeline () {
+        let log = Arc::new (Mutex::new (Vec::new ()));
+        let stage = |name: &'static str, f: fn(u32) -> u32| {
+            let log = log.clone ();
+            primitive (move |rt: &mut Runtime, i: u32, next: Box<Continuation<u32> + Send>| {
+                log.lock ().unwrap ().push ((rt.clock (), name, i));
+                next.call_box (rt, f (i));
+            })
+        };
+        let p = pipeline (stage ("double", |i| { i * 2 }).pipe (stage ("succ", |i| { i + 1 })));
+        assert_eq! (p.execute_par (2, vec! [1, 2, 3]), vec! [3, 5, 7]);
+        let mut log = log.lock ().unwrap ().clone ();
+        log.sort ();
+        assert_eq! (log, vec! [(0, "double", 1),
+                               (1, "double", 2), (1, "succ", 2),
+                               (2, "double", 3), (2, "succ", 4),
+                               (3, "succ", 6)]);
+
+        let p = pipeline (map (|i: u32| { i }).pipe (pause ()).pipe (map (|i: u32| { i * 10 })));
+        assert_eq! (p.execute_seq (vec! [1, 2, 3, 4]), vec! [10, 20, 30, 40]);
+    }
+
+    #[test]
+    fn test_par_chunks_map () {
+        let p = par_chunks_map (|i: u32| { i * i }).bind ( pause () );
+        let input: Vec<u32> = (0..1000).collect ();
+        let expected: Vec<u32> = input.iter ().map (|i| { i * i }).collect ();
+        assert_eq! (p.execute_par (4, input), expected);
+        let threads = primitive (|rt: &mut Runtime, (), next: Box<Continuation<usize> + Send>| {
+            let n = rt.threads ();
+            next.call_box (rt, n);
+        });
+        assert_eq! (threads.execute_par (4, ()), 4);
+        assert_eq! (par_chunks_map (|i: u32| { i + 1 }).execute_seq (vec! [1, 2, 3]), vec! [2, 3, 4]);
+        assert_eq! (par_chunks_map (|i: u32| { i }).execute_seq (Vec::new ()), Vec::<u32>::new ());
+    }
+
+    #[test]
+    fn test_shared () {
+        let count = Arc::new (Mutex::new (0));
+        let c = count.clone ();
+        let p = map (move |i: u32| { *c.lock ().unwrap () += 1; i + 1 })
+            .bind ( pause () )
+            .shared ();
+        let q = product (p.clone (), p.clone ()).bind ( map (|(a, b): (u32, u32)| { a + b }) );
+        assert_eq! (q.bind (p).execute_par (2, (1, 10)), 14);
+        assert_eq! (*count.lock ().unwrap (), 3);
+    }
+
+    #[test]
+    fn test_pre () {
+        let key = PureSignal::new ();
+        let v = ValueSignal::new (Box::new (|a: u32, b: u32| -> u32 {a+b}));
+        let pressed = Arc::new (Mutex::new (Vec::new ()));
+        let p = pressed.clone ();
+        let edges = fixpoint::<(),(),_> (
+            key.present (
+                key.pre ()
+                .bind ( map (move |pre: bool| { if ! pre { p.lock ().unwrap ().push (true); } }) )
+                .bind ( pause () )
+            ,   identity ()
+            )
+            .bind ( v.pre_value () )
+            .bind ( map (|pre: Option<u32>| { if pre == Option::Some (3) { Result::Err (()) } else { Result::Ok (()) } }) )
+        );
+        let emitter = key.emit ()
+            .bind ( pause () )
+            .bind ( key.emit () )
+            .bind ( pause () )
+            .bind ( pause () )
+            .bind ( key.emit () )
+            .bind ( value::<(),u32> (1) )
+            .bind ( v.emit () )
+            .bind ( value::<(),u32> (2) )
+            .bind ( v.emit () );
+        assert_eq! (product (edges, emitter).execute_seq (((), ())), ((), ()));
+        assert_eq! (*pressed.lock ().unwrap (), vec! [true, true]);
+    }
+
+    #[test]
+    fn test_gather_signal () {
+        let all = ValueSignal::with_default (Vec::new (), Box::new (|mut v: Vec<&'static str>, a: &'static str| {
+            v.push (a);
+            v
+        }));
+        let count = ValueSignal::with_default (0, Box::new (|n: usize, _: u32| { n + 1 }));
+        let p = product (
+            value::<(),&'static str> ("a")
+            .bind ( all.emit () )
+            .bind ( value::<(),&'static str> ("b") )
+            .bind ( all.emit () )
+            .bind ( value::<(),u32> (7) )
+            .bind ( count.emit () )
+            .bind ( value::<(),u32> (7) )
+            .bind ( count.emit () )
+            .bind ( value::<(),u32> (7) )
+            .bind ( count.emit () )
+        ,   all.await ().par_fanout ( count.await () )
+        );
+        assert_eq! (p.execute_seq (((),())), ((), (vec! ["a", "b"], 3)));
+    }
+
+    #[test]
+    fn test_behaviour () {
+        let position = Behaviour::new (0u32);
+        let outside = position.clone ();
+        let step = position.get ()
+            .bind ( map (|x: u32| { x + 1 }) )
+            .bind ( position.set () )
+            .bind ( position.get () );
+        let p = step.shared ();
+        let moves = p.clone ()
+            .bind ( pause () )
+            .bind ( map (|_: u32| {}) )
+            .bind ( p )
+            .bind ( pause () );
+        assert_eq! (moves.execute_seq (()), 1);
+        assert_eq! (outside.read (), 2);
+
+        let s = ValueSignal::new (Box::new (|a: u32, b: u32| -> u32 {a+b}));
+        let p = value::<(),u32> (5)
+            .bind ( s.emit () )
+            .bind ( s.last () )
+            .bind ( pause () )
+            .bind ( pause () )
+            .bind ( map (|before: Option<u32>| { ((), before) }) )
+            .bind ( s.last ().first () );
+        assert_eq! (p.execute_seq (()), (Option::Some (5), Option::None));
+    }
+
+    #[test]
+    fn test_await_when () {
+        let (s, received) = UniqSignal::new (Box::new (|a: u32, b: u32| -> u32 {a+b}));
+        let u = ValueSignal::new (Box::new (|_: Option<u32>, b: Option<u32>| b));
+        let values = Arc::new (Mutex::new (Vec::new ()));
+        let p1 = arrow!(
+            emit s, 1;
+            emit u, Option::None;
+            pause;
+            emit s, 2;
+            emit u, Option::Some (20);
+            pause;
+            emit s, 3;
+            emit u, Option::Some (30)
+        );
+        let v = values.clone ();
+        let p2 = received.await_when (|v: &u32| *v >= 2)
+            .bind ( map (move |i: Result<u32,AlreadyAwaited>| { v.lock ().unwrap ().push (i.unwrap ()); }) );
+        let v = values.clone ();
+        let p3 = arrow!(
+            await u, Option::Some (i) => { i };
+            mv i => { v.lock ().unwrap ().push (i); }
+        );
+        arrow!(
+            || p1;
+            || p2;
+            || p3
+        ).execute_seq (());
+        assert_eq! (*values.lock ().unwrap (), vec! [2, 20]);
+    }
+
+    #[test]
+    fn test_with_cancel_par () {
+        let s = ValueSignal::new (Box::new (|a: u32, b: u32| -> u32 {a+b}));
+        let t = Token::new (Option::None);
+        let external = t.clone ();
+        let p = product (
+            with_cancel (t, s.await ())
+        ,   map (move |()| {
+                let external = external.clone ();
+                thread::spawn (move || { external.request (); }).join ().unwrap ();
+            })
+        );
+        assert_eq! (p.execute_par (2, ((),())), (Option::None, ()));
+    }
+
+    #[test]
+    fn test_join_hooks () {
+        let hooks = Arc::new (Mutex::new (0));
+        let h = hooks.clone ();
+        let body = fixpoint (
+            fork_handle ( pause () )
+            .bind ( join () )
+            .bind ( primitive (move |rt: &mut Runtime, i: Option<u32>, next: Box<Continuation<Result<u32,u32>> + Send>| {
+                let mut max = h.lock ().unwrap ();
+                *max = ::std::cmp::max (*max, rt.token ().unwrap ().hooks ());
+                let i = i.unwrap () + 1;
+                next.call_box (rt, if i < 100 { Result::Ok (i) } else { Result::Err (i) });
+            }) )
+        );
+        let p = with_cancel (Token::new (Option::None), body);
+        assert_eq! (p.execute_seq (0), Option::Some (100));
+        assert! (*hooks.lock ().unwrap () <= 2);
+    }
+
+    #[test]
+    fn test_deep_bind () {
+        let mut p = map (|n: u32| { n + 1 }).boxed ();
+        for _ in 1..10_000 {
+            p = map (|n: u32| { n + 1 }).bind (p).boxed ();
+        }
+        assert_eq! (p.execute_seq (0), 10_000);
+    }
+
+    #[test]
+    fn test_uniq_signal_takeover () {
+        let (key, await) = UniqSignal::new (Box::new (|_: u32, b: u32| -> u32 {b}));
+        let log = Arc::new (Mutex::new (Vec::new ()));
+        let l = log.clone ();
+        let waiting = fixpoint (
+            map (|()| { ((),()) })
+            .bind ( select (await, pause ().bind ( pause () )) )
+            .bind ( map (move |e: Either<Result<u32,AlreadyAwaited>,()>| {
+                let mut log = l.lock ().unwrap ();
+                match e {
+                    Either::Left (k)  => { log.push (format! ("{:?}", k)); Result::Err (()) },
+                    Either::Right (()) => { log.push (String::from ("tick")); Result::Ok (()) },
+                }
+            }) )
+        );
+        let p = product (
+            waiting
+        ,   pause ()
+            .bind ( pause () )
+            .bind ( value::<(),u32> (7) )
+            .bind ( key.emit () )
+        );
+        p.execute_seq (((),()));
+        assert_eq! (*log.lock ().unwrap (), vec! ["tick", "Ok(7)"]);
+    }
+
 }
 
@@ -115,6 +115,17 @@ macro_rules! arrow {
         $s.await_immediate().bind(arrow!($($y)+))
     );
 
+    (await $s:expr, $p:pat => $f:block) => (
+        $s.await_when(|v| match v { #[allow(unused_variables)] $p => true, _ => false })
+          .bind(map(|v| match v { $p => $f, #[allow(unreachable_patterns)] _ => unreachable!() }))
+    );
+
+    (await $s:expr, $p:pat => $f:block; $($y:tt)+) => (
+        $s.await_when(|v| match v { #[allow(unused_variables)] $p => true, _ => false })
+          .bind(map(|v| match v { $p => $f, #[allow(unreachable_patterns)] _ => unreachable!() }))
+          .bind(arrow!($($y)+))
+    );
+
     (await $s:expr) => (
         $s.await()
     );
@@ -1,8 +1,9 @@
 
 use std::thread;
-use std::sync::{Arc,Mutex};
+use std::any::{Any};
+use std::sync::{Arc,Mutex,Weak};
 use std::cell::{RefCell};
-use std::mem::{swap};
+use std::mem::{swap,replace};
 use std::option::{Option};
 use std::collections::VecDeque;
 
@@ -29,6 +30,203 @@ where F: FnOnce(&mut Runtime, V) + 'static {
     }
 }
 
+//  _____     _              
+// |_   _|__ | | _____ _ __  
+//   | |/ _ \| |/ / _ \ '_ \ 
+//   | | (_) |   <  __/ | | |
+//   |_|\___/|_|\_\___|_| |_|
+//                           
+
+// A token marks the continuations registered while it is the current token
+// of the runtime. Once cancelled, these continuations are dropped instead of
+// being called, starting with the instant following the cancellation. The
+// continuations parked elsewhere (e.g. in a signal) are purged at the end of
+// the cancellation instant by the hooks registered with `on_cancel`, or with
+// `park` by the structures holding them; a hook is dropped with the data it
+// purges. A cancellation requested from outside of the runtime with `request`
+// takes effect at the next instant boundary of the runtimes watching the
+// token.
+
+struct TokenData {
+    cancelled : Option<u64>,
+    requested : bool,
+    parents   : Vec<Token>,
+    children  : Vec<Weak<Mutex<TokenData>>>,
+    purges    : Vec<(usize, Weak<Any + Send + Sync>, Box<Fn(&mut Runtime) + Send>)>,
+}
+
+#[derive(Clone)]
+pub struct Token {
+    data: Arc<Mutex<TokenData>>,
+}
+
+impl Token {
+
+    pub fn new (parent: Option<Token>) -> Token {
+        let token = Token {data: Arc::new (Mutex::new (TokenData {
+            cancelled : Option::None,
+            requested : false,
+            parents   : Vec::new (),
+            children  : Vec::new (),
+            purges    : Vec::new (),
+        }))};
+        if let Option::Some (parent) = parent {
+            token.attach (&parent);
+        }
+        token
+    }
+
+    pub fn attach (&self, parent: &Token) {
+        {
+            let mut data = parent.data.lock ().unwrap ();
+            data.children.retain (|child| child.upgrade ().is_some ());
+            data.children.push (Arc::downgrade (&self.data));
+        }
+        let mut data = self.data.lock ().unwrap ();
+        data.parents.push (parent.clone ());
+    }
+
+    pub fn cancel (&self, rt: &mut Runtime) {
+        {
+            let mut data = self.data.lock ().unwrap ();
+            if let Option::Some (_) = data.cancelled { return; }
+            data.cancelled = Option::Some (rt.clock ());
+        }
+        let token = self.clone ();
+        let old = rt.set_token (Option::None);
+        rt.on_end_of_instant (Box::new (move |rt: &mut Runtime, ()| {
+            token.purge (rt);
+        }));
+        rt.set_token (old);
+    }
+
+    pub fn request (&self) {
+        let mut data = self.data.lock ().unwrap ();
+        data.requested = true;
+    }
+
+    pub fn is_cancelled (&self, rt: &Runtime) -> bool {
+        let parents = {
+            let data = self.data.lock ().unwrap ();
+            if let Option::Some (date) = data.cancelled {
+                if date < rt.clock () { return true; }
+            }
+            data.parents.clone ()
+        };
+        parents.iter ().any (|parent| parent.is_cancelled (rt))
+    }
+
+    // Unlike `is_cancelled`, already true during the cancellation instant.
+    pub fn is_cancelling (&self) -> bool {
+        let parents = {
+            let data = self.data.lock ().unwrap ();
+            if let Option::Some (_) = data.cancelled { return true; }
+            data.parents.clone ()
+        };
+        parents.iter ().any (|parent| parent.is_cancelling ())
+    }
+
+    pub fn on_cancel<T> (&self, owner: &Arc<T>, purge: Box<Fn(&mut Runtime) + Send>)
+    where T: Send + Sync + 'static
+    {
+        let key = &**owner as *const T as usize;
+        let weak : Weak<Any + Send + Sync> = Arc::downgrade (owner) as Weak<T>;
+        let mut data = self.data.lock ().unwrap ();
+        data.purges.retain (|&(_, ref owner, _)| owner.upgrade ().is_some ());
+        if data.purges.iter ().any (|&(k,_,_)| k == key) { return; }
+        data.purges.push ((key, weak, purge));
+    }
+
+    #[cfg(test)]
+    pub fn hooks (&self) -> usize {
+        self.data.lock ().unwrap ().purges.len ()
+    }
+
+    fn purge (&self, rt: &mut Runtime) {
+        let (purges, children) = {
+            let mut data = self.data.lock ().unwrap ();
+            let children : Vec<_> = data.children.iter ()
+                .filter_map (|child| child.upgrade ())
+                .collect ();
+            (replace (&mut data.purges, Vec::new ()), children)
+        };
+        for (_, _, purge) in purges {
+            purge (rt);
+        }
+        for child in children {
+            Token {data: child}.purge (rt);
+        }
+    }
+
+}
+
+pub fn is_alive (token: &Option<Token>, rt: &Runtime) -> bool {
+    match *token {
+        Option::Some (ref token) => !token.is_cancelled (rt),
+        Option::None => true,
+    }
+}
+
+fn watch_token (watched: &mut Vec<Token>, token: Token) {
+    if watched.iter ().any (|t| Arc::ptr_eq (&t.data, &token.data)) { return; }
+    watched.push (token);
+}
+
+fn poll_requests (watched: &mut Vec<Token>, date: u64) -> Vec<Token> {
+    let mut cancelled = Vec::new ();
+    watched.retain (|token| {
+        let mut data = token.data.lock ().unwrap ();
+        if let Option::Some (_) = data.cancelled { return false; }
+        if data.requested {
+            data.cancelled = Option::Some (date);
+            cancelled.push (token.clone ());
+            return false;
+        }
+        true
+    });
+    cancelled
+}
+
+pub fn park<T,F> (rt: &Runtime, data: &Arc<T>, purge: F) -> Option<Token>
+where T: Send + Sync + 'static,
+      F: Fn(&T, &mut Runtime) + Send + 'static
+{
+    let token = rt.token ();
+    if let Option::Some (ref token) = token {
+        let weak = Arc::downgrade (data);
+        token.on_cancel (data, Box::new (move |rt: &mut Runtime| {
+            if let Option::Some (data) = weak.upgrade () {
+                purge (&*data, rt);
+            }
+        }));
+    }
+    token
+}
+
+pub fn with_token<R,F> (rt: &mut Runtime, token: Option<Token>, f: F) -> R
+where F: FnOnce(&mut Runtime) -> R
+{
+    let old = rt.set_token (token);
+    let r = f (rt);
+    rt.set_token (old);
+    r
+}
+
+pub fn guard<V> (token: Option<Token>, c: Box<Continuation<V> + Send>)
+    -> Box<Continuation<V> + Send>
+where V: 'static
+{
+    match token {
+        Option::None => c,
+        Option::Some (token) => Box::new (move |rt: &mut Runtime, v: V| {
+            if token.is_cancelled (rt) { return; }
+            with_token (rt, Option::Some (token), move |rt: &mut Runtime| {
+                c.call_box (rt, v);
+            });
+        }),
+    }
+}
+
 //  ____              _   _                
 // |  _ \ _   _ _ __ | |_(_)_ __ ___   ___ 
 // | |_) | | | | '_ \| __| | '_ ` _ \ / _ \
@@ -44,6 +242,12 @@ pub trait Runtime {
     fn on_next_instant    (&mut self, c: Box<Continuation<()> + Send>);
     fn on_end_of_instant  (&mut self, c: Box<Continuation<()> + Send>);
 
+    fn clock     (&self) -> u64;
+    fn token     (&self) -> Option<Token>;
+    fn set_token (&mut self, token: Option<Token>) -> Option<Token>;
+    fn watch     (&mut self, token: Token);
+    fn threads   (&self) -> usize;
+
 }
 
 //  ____             ____              _   _                
@@ -57,6 +261,9 @@ pub struct SeqRuntime {
 	current_instant : VecDeque <Box<Continuation<()> + Send>>,
 	endof_instant   : VecDeque <Box<Continuation<()> + Send>>,
 	next_instant    : VecDeque <Box<Continuation<()> + Send>>,
+	clock           : u64,
+	token           : Option<Token>,
+	watched         : Vec<Token>,
 }
 
 impl SeqRuntime {
@@ -65,13 +272,21 @@ impl SeqRuntime {
         current_instant : VecDeque::new (),
         endof_instant   : VecDeque::new (),
         next_instant    : VecDeque::new (),
+        clock           : 0,
+        token           : Option::None,
+        watched         : Vec::new (),
     }}
 
     pub fn instant (&mut self) -> bool {
         while let Some (ct) = self.current_instant.pop_front () {
             Continuation::call_box (ct, self, ());
         };
+        let cancelled = poll_requests (&mut self.watched, self.clock);
+        self.clock = self.clock + 1;
         swap (&mut self.current_instant, &mut self.next_instant);
+        for token in cancelled {
+            token.purge (self);
+        }
         while let Some (ct) = self.endof_instant.pop_front () {
             Continuation::call_box (ct, self, ());
         };
@@ -87,15 +302,35 @@ impl Runtime for SeqRuntime {
     }
 
 	fn on_current_instant (&mut self, c: Box<Continuation<()> + Send>) {
-		self.current_instant.push_back (c)
+		self.current_instant.push_back (guard (self.token.clone (), c))
 	}
 
 	fn on_next_instant    (&mut self, c: Box<Continuation<()> + Send>) {
-		self.next_instant.push_back (c)
+		self.next_instant.push_back (guard (self.token.clone (), c))
 	}
 
 	fn on_end_of_instant  (&mut self, c: Box<Continuation<()> + Send>) {
-		self.endof_instant.push_back (c)
+		self.endof_instant.push_back (guard (self.token.clone (), c))
+	}
+
+	fn clock (&self) -> u64 {
+		self.clock
+	}
+
+	fn token (&self) -> Option<Token> {
+		self.token.clone ()
+	}
+
+	fn set_token (&mut self, token: Option<Token>) -> Option<Token> {
+		replace (&mut self.token, token)
+	}
+
+	fn watch (&mut self, token: Token) {
+		watch_token (&mut self.watched, token)
+	}
+
+	fn threads (&self) -> usize {
+		1
 	}
 
 }
@@ -112,12 +347,17 @@ struct ParRuntimeCommon {
     endof_instant   : VecDeque <Box<Continuation<()> + Send>>,
     next_instant    : VecDeque <Box<Continuation<()> + Send>>,
     working         : u32,
+    threads         : usize,
     running         : bool,
+    clock           : u64,
+    watched         : Vec<Token>,
 }
 
 pub struct ParRuntime {
-    base : Arc<Mutex<RefCell<ParRuntimeCommon>>>,
-    next : Option<Box<Continuation<()> + Send>>,
+    base  : Arc<Mutex<RefCell<ParRuntimeCommon>>>,
+    next  : Option<Box<Continuation<()> + Send>>,
+    clock : u64,
+    token : Option<Token>,
 }
 
 impl ParRuntime {
@@ -129,16 +369,28 @@ impl ParRuntime {
             next_instant    : VecDeque::new (),
             running         : true,
             working         : 0,
+            threads         : 1,
+            clock           : 0,
+            watched         : Vec::new (),
         }))),
-        next : Option::None,
+        next  : Option::None,
+        clock : 0,
+        token : Option::None,
     }}
 
     pub fn spawn (&self) {
+        {
+            let base = self.base.lock ().unwrap ();
+            let mut base = base.borrow_mut ();
+            base.threads = base.threads + 1;
+        }
         let base = self.base.clone ();
         thread::spawn(move || {
             let mut child = ParRuntime {
-                base : base,
-                next : Option::None,
+                base  : base,
+                next  : Option::None,
+                clock : 0,
+                token : Option::None,
             };
             {
                 let base = child.base.lock ().unwrap ();
@@ -150,6 +402,7 @@ impl ParRuntime {
                     let base = child.base.lock ().unwrap ();
                     let mut base = base.borrow_mut ();
                     child.next = base.current_instant.pop_front ();
+                    child.clock = base.clock;
                     if child.next.is_none () {
                         base.working = base.working - 1;
                     }
@@ -160,6 +413,7 @@ impl ParRuntime {
                     let mut base = base.borrow_mut ();
                     if !base.running { return; }
                     child.next = base.current_instant.pop_front ();
+                    child.clock = base.clock;
                     if !child.next.is_none () {
                         base.working = base.working + 1;
                     }
@@ -183,7 +437,15 @@ impl Runtime for ParRuntime {
                 self.next = base.current_instant.pop_front ();
                 if self.next.is_none () {
                     if base.working == 0 {
+                        let clock = base.clock;
+                        let cancelled = poll_requests (&mut base.watched, clock);
+                        base.clock = base.clock + 1;
                         let mut temp = VecDeque::new ();
+                        for token in cancelled {
+                            temp.push_back (Box::new (move |rt: &mut Runtime, ()| {
+                                token.purge (rt);
+                            }) as Box<Continuation<()> + Send>);
+                        }
                         temp.append (&mut base.endof_instant);
                         temp.append (&mut base.next_instant);
                         base.current_instant.append (&mut temp);
@@ -194,6 +456,7 @@ impl Runtime for ParRuntime {
                         thread::yield_now ();
                     }
                 }
+                self.clock = base.clock;
             }
             while let Some (ct) = self.next.take () {
                 Continuation::call_box (ct, self, ());
@@ -202,6 +465,7 @@ impl Runtime for ParRuntime {
     }
 
     fn on_current_instant (&mut self, c: Box<Continuation<()> + Send>) {
+        let c = guard (self.token.clone (), c);
         if self.next.is_none () {
             self.next = Option::Some (c);
         } else {
@@ -214,13 +478,37 @@ impl Runtime for ParRuntime {
     fn on_next_instant    (&mut self, c: Box<Continuation<()> + Send>) {
         let base = self.base.lock ().unwrap ();
         let mut base = base.borrow_mut ();
-        base.next_instant.push_back (c);
+        base.next_instant.push_back (guard (self.token.clone (), c));
     }
 
     fn on_end_of_instant  (&mut self, c: Box<Continuation<()> + Send>) {
         let base = self.base.lock ().unwrap ();
         let mut base = base.borrow_mut ();
-        base.endof_instant.push_back (c);
+        base.endof_instant.push_back (guard (self.token.clone (), c));
+    }
+
+    fn clock (&self) -> u64 {
+        self.clock
+    }
+
+    fn token (&self) -> Option<Token> {
+        self.token.clone ()
+    }
+
+    fn set_token (&mut self, token: Option<Token>) -> Option<Token> {
+        replace (&mut self.token, token)
+    }
+
+    fn watch (&mut self, token: Token) {
+        let base = self.base.lock ().unwrap ();
+        let mut base = base.borrow_mut ();
+        watch_token (&mut base.watched, token);
+    }
+
+    fn threads (&self) -> usize {
+        let base = self.base.lock ().unwrap ();
+        let base = base.borrow ();
+        base.threads
     }
 
 }
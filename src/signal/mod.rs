@@ -4,8 +4,8 @@ use std::cell::{RefCell};
 use std::option::{Option};
 use std::mem::{swap};
 
-use runtime::{Runtime,Continuation};
-use arrow::{Arrow};
+use runtime::{Runtime,Continuation,Token,with_token};
+use arrow::{Arrow,Description};
 
 pub mod prim;
 
@@ -18,6 +18,10 @@ pub mod prim;
 
 pub trait Signal : Sized + Clone + Send + 'static {
 
+    fn id (&self) -> usize;
+
+    fn call_pre (&self, rt: &mut Runtime) -> bool;
+
     fn call_await_immediate (&self, rt: &mut Runtime,
                              next: Box<Continuation<()> + Send>);
 
@@ -42,6 +46,16 @@ pub trait Signal : Sized + Clone + Send + 'static {
         }
     }
 
+    fn pre (&self) -> Pre<Self> {
+        Pre {signal: self.clone ()}
+    }
+
+    fn boxed (&self) -> BoxedSignal
+    where Self: Sync
+    {
+        BoxedSignal {signal: Arc::new (self.clone ())}
+    }
+
 }
 
 //     _                _ _   ___                              _ _       _       
@@ -67,6 +81,10 @@ where A: Send + 'static,
         }));
     }
 
+    fn describe (&self) -> Description {
+        Description::signal ("await immediate", vec! [self.signal.id ()], vec! [])
+    }
+
 }
 
 //  ____                           _   
@@ -128,5 +146,258 @@ where A: Send + 'static,
         );
     }
 
+    fn describe (&self) -> Description {
+        Description::signal ("present", vec! [self.signal.id ()], vec! [self.ifp.describe (), self.ifn.describe ()])
+    }
+
+}
+
+//  ____           
+// |  _ \ _ __ ___ 
+// | |_) | '__/ _ \
+// |  __/| | |  __/
+// |_|   |_|  \___|
+//                 
+
+// Whether the signal was emitted during the previous instant.
+
+pub struct Pre<S> {
+    signal: S,
+}
+
+impl<S> Arrow<(),bool> for Pre<S>
+where S: Signal,
+{
+
+    fn call<F> (&self, rt: &mut Runtime, (): (), next: F)
+    where F: Continuation<bool> + Send {
+        let pre = self.signal.call_pre (rt);
+        next.call (rt, pre);
+    }
+
+    fn describe (&self) -> Description {
+        Description::signal ("pre", vec! [self.signal.id ()], vec! [])
+    }
+
+}
+
+//  ____                    _ ____  _                   _ 
+// | __ )  _____  _____  __| / ___|(_) __ _ _ __   __ _| |
+// |  _ \ / _ \ \/ / _ \/ _` \___ \| |/ _` | '_ \ / _` | |
+// | |_) | (_) >  <  __/ (_| |___) | | (_| | | | | (_| | |
+// |____/ \___/_/\_\___|\__,_|____/|_|\__, |_| |_|\__,_|_|
+//                                    |___/               
+
+trait SignalObject : Send + Sync {
+
+    fn id_box (&self) -> usize;
+
+    fn pre_box (&self, rt: &mut Runtime) -> bool;
+
+    fn await_immediate_box (&self, rt: &mut Runtime,
+                            next: Box<Continuation<()> + Send>);
+
+    fn present_box (&self, rt: &mut Runtime,
+                    ifp: Box<Continuation<()> + Send>,
+                    ifn: Box<Continuation<()> + Send>);
+
+}
+
+impl<S> SignalObject for S
+where S: Signal + Sync
+{
+
+    fn id_box (&self) -> usize {
+        self.id ()
+    }
+
+    fn pre_box (&self, rt: &mut Runtime) -> bool {
+        self.call_pre (rt)
+    }
+
+    fn await_immediate_box (&self, rt: &mut Runtime,
+                            next: Box<Continuation<()> + Send>)
+    {
+        self.call_await_immediate (rt, next);
+    }
+
+    fn present_box (&self, rt: &mut Runtime,
+                    ifp: Box<Continuation<()> + Send>,
+                    ifn: Box<Continuation<()> + Send>)
+    {
+        self.call_present (rt, ifp, ifn);
+    }
+
+}
+
+#[derive(Clone)]
+pub struct BoxedSignal {
+    signal: Arc<SignalObject>,
+}
+
+impl Signal for BoxedSignal {
+
+    fn id (&self) -> usize {
+        self.signal.id_box ()
+    }
+
+    fn call_pre (&self, rt: &mut Runtime) -> bool {
+        self.signal.pre_box (rt)
+    }
+
+    fn call_await_immediate (&self, rt: &mut Runtime,
+                             next: Box<Continuation<()> + Send>)
+    {
+        self.signal.await_immediate_box (rt, next);
+    }
+
+    fn call_present (&self, rt: &mut Runtime,
+                     ifp: Box<Continuation<()> + Send>,
+                     ifn: Box<Continuation<()> + Send>)
+    {
+        self.signal.present_box (rt, ifp, ifn);
+    }
+
+}
+
+//     _                _ _      _                
+//    / \__      ____ _(_) |_   / \   _ __  _   _ 
+//   / _ \ \ /\ / / _` | | __| / _ \ | '_ \| | | |
+//  / ___ \ V  V / (_| | | |_ / ___ \| | | | |_| |
+// /_/   \_\_/\_/ \__,_|_|\__/_/   \_\_| |_|\__, |
+//                                          |___/ 
+
+// Resumes in the first instant where one of the signals is present, with the
+// (sorted) indices of the signals present by then: a signal emitted later in
+// the same instant is not reported.
+
+pub struct AwaitAny {
+    signals: Vec<BoxedSignal>,
+}
+
+pub fn await_any (signals: Vec<BoxedSignal>) -> AwaitAny {
+    AwaitAny {signals: signals}
+}
+
+struct AwaitAnyState<F> {
+    present: Vec<usize>,
+    next:    Option<F>,
+}
+
+impl Arrow<(),Vec<usize>> for AwaitAny {
+
+    fn call<F> (&self, rt: &mut Runtime, (): (), next: F)
+    where F: Continuation<Vec<usize>> + Send {
+        if self.signals.is_empty () {
+            return next.call (rt, Vec::new ());
+        }
+        let parent = rt.token ();
+        let token = Token::new (parent.clone ());
+        let state = Arc::new (Mutex::new (AwaitAnyState {
+            present: Vec::new (),
+            next:    Option::Some (next),
+        }));
+        with_token (rt, Option::Some (token.clone ()), |rt: &mut Runtime| {
+            for (i, signal) in self.signals.iter ().enumerate () {
+                let state = state.clone ();
+                let token = token.clone ();
+                let parent = parent.clone ();
+                signal.call_await_immediate (rt, Box::new (move |rt: &mut Runtime, ()| {
+                    {
+                        let mut st = state.lock ().unwrap ();
+                        if st.next.is_none () { return; }
+                        st.present.push (i);
+                        if st.present.len () > 1 { return; }
+                    }
+                    token.cancel (rt);
+                    with_token (rt, parent, move |rt: &mut Runtime| {
+                        rt.on_current_instant (Box::new (move |rt: &mut Runtime, ()| {
+                            let (mut present, next) = {
+                                let mut st = state.lock ().unwrap ();
+                                let mut present = Vec::new ();
+                                swap (&mut present, &mut st.present);
+                                (present, st.next.take ())
+                            };
+                            present.sort ();
+                            if let Option::Some (next) = next {
+                                next.call (rt, present);
+                            }
+                        }));
+                    });
+                }));
+            }
+        });
+    }
+
+    fn describe (&self) -> Description {
+        Description::signal ("await any", self.signals.iter ().map (|s| { s.id () }).collect (), vec! [])
+    }
+
 }
 
+//     _                _ _      _    _ _ 
+//    / \__      ____ _(_) |_   / \  | | |
+//   / _ \ \ /\ / / _` | | __| / _ \ | | |
+//  / ___ \ V  V / (_| | | |_ / ___ \| | |
+// /_/   \_\_/\_/ \__,_|_|\__/_/   \_\_|_|
+//                                        
+
+// Resumes as soon as every signal has been emitted, in the same instant or
+// over several ones.
+
+pub struct AwaitAll {
+    signals: Vec<BoxedSignal>,
+}
+
+pub fn await_all (signals: Vec<BoxedSignal>) -> AwaitAll {
+    AwaitAll {signals: signals}
+}
+
+struct AwaitAllState<A,F> {
+    missing: usize,
+    val:     Option<A>,
+    next:    Option<F>,
+}
+
+impl<A> Arrow<A,A> for AwaitAll
+where A: Send + 'static
+{
+
+    fn call<F> (&self, rt: &mut Runtime, a: A, next: F)
+    where F: Continuation<A> + Send {
+        if self.signals.is_empty () {
+            return next.call (rt, a);
+        }
+        let parent = rt.token ();
+        let token = Token::new (parent.clone ());
+        let state = Arc::new (Mutex::new (AwaitAllState {
+            missing: self.signals.len (),
+            val:     Option::Some (a),
+            next:    Option::Some (next),
+        }));
+        with_token (rt, Option::Some (token.clone ()), |rt: &mut Runtime| {
+            for signal in self.signals.iter () {
+                let state = state.clone ();
+                let token = token.clone ();
+                let parent = parent.clone ();
+                signal.call_await_immediate (rt, Box::new (move |rt: &mut Runtime, ()| {
+                    let (a, next) = {
+                        let mut st = state.lock ().unwrap ();
+                        st.missing = st.missing - 1;
+                        if st.missing > 0 { return; }
+                        (st.val.take ().unwrap (), st.next.take ().unwrap ())
+                    };
+                    token.cancel (rt);
+                    with_token (rt, parent, move |rt: &mut Runtime| {
+                        next.call (rt, a);
+                    });
+                }));
+            }
+        });
+    }
+
+    fn describe (&self) -> Description {
+        Description::signal ("await all", self.signals.iter ().map (|s| { s.id () }).collect (), vec! [])
+    }
+
+}
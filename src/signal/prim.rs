@@ -2,11 +2,12 @@
 use std::sync::{Arc,Mutex};
 use std::cell::{RefCell};
 use std::option::{Option};
+use std::result::{Result};
 use std::collections::{VecDeque};
 use std::mem::{swap};
 
-use runtime::{Runtime,Continuation};
-use arrow::{Arrow};
+use runtime::{Runtime,Continuation,Token,guard,is_alive,park};
+use arrow::{Arrow,Description};
 use signal::{Signal};
 
 //  ____                 ____  _                   _ 
@@ -18,9 +19,11 @@ use signal::{Signal};
 
 struct PureSignalRuntime {
     emitted : bool,
-    waiters : VecDeque<Box<Continuation<()> + Send>>,
-    present : VecDeque<(Box<Continuation<()> + Send>,Box<Continuation<()> + Send>)>,
+    waiters : VecDeque<(Option<Token>,Box<Continuation<()> + Send>)>,
+    present : VecDeque<(Option<Token>,Box<Continuation<()> + Send>,Box<Continuation<()> + Send>)>,
     awaken  : bool,
+    last    : Option<u64>,
+    prev    : Option<u64>,
 }
 
 #[derive(Clone)]
@@ -39,6 +42,8 @@ impl PureSignal {
                 waiters: VecDeque::new (),
                 present: VecDeque::new (),
                 awaken : false,
+                last   : Option::None,
+                prev   : Option::None,
             },
         )))}
     }
@@ -51,22 +56,44 @@ impl PureSignal {
         if data.awaken {} else {
             data.awaken = true;
             let signal = self.clone ();
+            let token = rt.set_token (Option::None);
             rt.on_end_of_instant (Box::new (move |rt: &mut Runtime, ()| {
                 let data = signal.rt.lock ().unwrap ();
                 let mut data = data.borrow_mut ();
                 (*data).emitted = false;
                 (*data).awaken  = false;
-                while let Option::Some ((_,ct)) = (*data).present.pop_front () {
+                while let Option::Some ((_,_,ct)) = (*data).present.pop_front () {
                     rt.on_current_instant (ct);
                 }
             }));
+            rt.set_token (token);
         }
     }
 
+    fn parked (&self, rt: &Runtime) -> Option<Token> {
+        park (rt, &self.rt, |data: &Mutex<RefCell<PureSignalRuntime>>, rt: &mut Runtime| {
+            let data = data.lock ().unwrap ();
+            let mut data = data.borrow_mut ();
+            (*data).waiters.retain (|&(ref token,_)| is_alive (token, rt));
+            (*data).present.retain (|&(ref token,_,_)| is_alive (token, rt));
+        })
+    }
+
 }
 
 impl Signal for PureSignal {
 
+    fn id (&self) -> usize {
+        &*self.rt as *const _ as usize
+    }
+
+    fn call_pre (&self, rt: &mut Runtime) -> bool {
+        let data = self.rt.lock ().unwrap ();
+        let data = data.borrow ();
+        let pre = if (*data).last == Option::Some (rt.clock ()) { (*data).prev } else { (*data).last };
+        pre.map_or (false, |date| date + 1 == rt.clock ())
+    }
+
     fn call_await_immediate (&self, rt: &mut Runtime,
                              next: Box<Continuation<()> + Send>)
     {
@@ -75,7 +102,8 @@ impl Signal for PureSignal {
         if (*data).emitted {
             rt.on_current_instant (next);
         } else {
-            (*data).waiters.push_back (next);
+            let token = self.parked (rt);
+            (*data).waiters.push_back ((token.clone (), guard (token, next)));
         }
     }
 
@@ -88,7 +116,8 @@ impl Signal for PureSignal {
         if (*data).emitted {
             rt.on_current_instant (ifp);
         } else {
-            (*data).present.push_back ((ifp,ifn));
+            let token = self.parked (rt);
+            (*data).present.push_back ((token.clone (), guard (token.clone (), ifp), guard (token, ifn)));
             self.awake (rt, &mut data);
         }
     }
@@ -102,19 +131,30 @@ impl Arrow<(),()> for EmitPureSignal {
         let &EmitPureSignal(ref signal) = self;
         let data = signal.rt.lock ().unwrap ();
         let mut data = data.borrow_mut ();
+        if (*data).last != Option::Some (rt.clock ()) {
+            (*data).prev = (*data).last;
+            (*data).last = Option::Some (rt.clock ());
+        }
         if (*data).emitted {} else {
             (*data).emitted = true;
-            while let Option::Some (ct) = (*data).waiters.pop_front () {
+            let token = rt.set_token (Option::None);
+            while let Option::Some ((_,ct)) = (*data).waiters.pop_front () {
                 rt.on_current_instant (ct);
             };
-            while let Option::Some ((ct,_)) = (*data).present.pop_front () {
+            while let Option::Some ((_,ct,_)) = (*data).present.pop_front () {
                 rt.on_current_instant (ct);
             }
+            rt.set_token (token);
             signal.awake (rt, &mut data);
         };
         rt.on_current_instant (Box::new (next));
     }
 
+    fn describe (&self) -> Description {
+        let &EmitPureSignal(ref signal) = self;
+        Description::signal ("emit", vec! [signal.id ()], vec! [])
+    }
+
 }
 
 // __     __    _            ____  _                   _ 
@@ -124,58 +164,136 @@ impl Arrow<(),()> for EmitPureSignal {
 //    \_/ \__,_|_|\__,_|\___|____/|_|\__, |_| |_|\__,_|_|
 //                                   |___/               
 
-struct ValueSignalRuntime<A> {
-    current : Option <A>,
-    combine : Box<Fn(A,A) -> A + Send>,
-    waiters : VecDeque<Box<Continuation<A> + Send>>,
+// Emissions of type A are folded into a value of type B: starting from
+// nothing for `new`, from the default for `with_default`.
+
+struct ValueSignalRuntime<A,B> {
+    current : Option <B>,
+    fold    : Box<Fn(Option<B>,A) -> B + Send>,
+    waiters : VecDeque<(Option<Token>,Option<Box<Fn(&B) -> bool + Send>>,Box<Continuation<B> + Send>)>,
     awaken  : bool,
+    last    : Option<(u64,B)>,
+    prev    : Option<(u64,B)>,
 }
 
-#[derive(Clone)]
-pub struct ValueSignal<A> {
+pub struct ValueSignal<A,B=A> {
     base : PureSignal,
-    data : Arc<Mutex<RefCell<ValueSignalRuntime<A>>>>,
+    data : Arc<Mutex<RefCell<ValueSignalRuntime<A,B>>>>,
 }
 
+impl<A,B> Clone for ValueSignal<A,B> {
+
+    fn clone (&self) -> ValueSignal<A,B> {
+        ValueSignal {
+            base: self.base.clone (),
+            data: self.data.clone (),
+        }
+    }
+
+}
+
+#[derive(Clone)]
+pub struct EmitValueSignal<A,B=A> (ValueSignal<A,B>);
+
 #[derive(Clone)]
-pub struct EmitValueSignal<A> (ValueSignal<A>);
+pub struct AwaitValueSignal<A,B=A> (ValueSignal<A,B>);
 
 #[derive(Clone)]
-pub struct AwaitValueSignal<A> (ValueSignal<A>);
+pub struct PreValueSignal<A,B=A> (ValueSignal<A,B>);
+
+#[derive(Clone)]
+pub struct LastValueSignal<A,B=A> (ValueSignal<A,B>);
+
+pub struct AwaitWhenValueSignal<A,B,P> {
+    signal : ValueSignal<A,B>,
+    when   : Arc<P>,
+}
+
+impl<A,B,P> Clone for AwaitWhenValueSignal<A,B,P> {
+
+    fn clone (&self) -> AwaitWhenValueSignal<A,B,P> {
+        AwaitWhenValueSignal {
+            signal: self.signal.clone (),
+            when: self.when.clone (),
+        }
+    }
+
+}
 
 impl<A> ValueSignal<A>
-where Self: Clone + 'static,
-      A: Clone + Send + 'static,
+where A: Clone + Send + 'static,
 {
 
-    pub fn new (combine: Box<Fn(A,A) -> A + Send>) -> ValueSignal<A>
-    where A: 'static,
-    {
+    pub fn new (combine: Box<Fn(A,A) -> A + Send>) -> ValueSignal<A> {
+        ValueSignal::from_fold (Box::new (move |current: Option<A>, a: A| {
+            match current {
+                Option::None => a,
+                Option::Some (current) => combine (current, a),
+            }
+        }))
+    }
+
+}
+
+impl<A,B> ValueSignal<A,B>
+where A: Send + 'static,
+      B: Clone + Send + 'static,
+{
+
+    pub fn with_default (default: B, gather: Box<Fn(B,A) -> B + Send>) -> ValueSignal<A,B> {
+        ValueSignal::from_fold (Box::new (move |current: Option<B>, a: A| {
+            gather (current.unwrap_or_else (|| default.clone ()), a)
+        }))
+    }
+
+    fn from_fold (fold: Box<Fn(Option<B>,A) -> B + Send>) -> ValueSignal<A,B> {
         ValueSignal {
             base: PureSignal::new (),
             data: Arc::new (Mutex::new (RefCell::new (
                 ValueSignalRuntime {
                     current : Option::None,
-                    combine : combine,
+                    fold    : fold,
                     waiters : VecDeque::new (),
                     awaken  : false,
+                    last    : Option::None,
+                    prev    : Option::None,
                 }
             ))),
         }
     }
 
-    pub fn emit (&self) -> EmitValueSignal<A> {
+    pub fn emit (&self) -> EmitValueSignal<A,B> {
         EmitValueSignal (self.clone ())
     }
 
-    pub fn await (&self) -> AwaitValueSignal<A> {
+    pub fn await (&self) -> AwaitValueSignal<A,B> {
         AwaitValueSignal (self.clone ())
     }
 
-    fn awake (&self, rt: &mut Runtime, data: &mut ValueSignalRuntime<A>) {
+    // Only resumes in an instant where the value satisfies `when`, and keeps
+    // waiting otherwise.
+    pub fn await_when<P> (&self, when: P) -> AwaitWhenValueSignal<A,B,P>
+    where P: Fn(&B) -> bool + Send + Sync + 'static
+    {
+        AwaitWhenValueSignal {
+            signal: self.clone (),
+            when: Arc::new (when),
+        }
+    }
+
+    pub fn pre_value (&self) -> PreValueSignal<A,B> {
+        PreValueSignal (self.clone ())
+    }
+
+    pub fn last (&self) -> LastValueSignal<A,B> {
+        LastValueSignal (self.clone ())
+    }
+
+    fn awake (&self, rt: &mut Runtime, data: &mut ValueSignalRuntime<A,B>) {
         if data.awaken {} else {
             data.awaken = true;
             let signal = self.clone ();
+            let token = rt.set_token (Option::None);
             rt.on_end_of_instant (Box::new (move |rt: &mut Runtime, ()| {
                 let data = signal.data.lock ().unwrap ();
                 let mut data = data.borrow_mut ();
@@ -185,24 +303,62 @@ where Self: Clone + 'static,
                 match current {
                     Option::None => {},
                     Option::Some (current) => {
-                        while let Option::Some (ct) = (*data).waiters.pop_front () {
-                            let current = current.clone ();
-                            rt.on_current_instant (Box::new (move |rt: &mut Runtime, ()| {
-                                ct.call_box (rt, current);
-                            }));
+                        let mut waiting = VecDeque::new ();
+                        while let Option::Some ((token,when,ct)) = (*data).waiters.pop_front () {
+                            let ready = match when {
+                                Option::Some (ref when) => when (&current),
+                                Option::None => true,
+                            };
+                            if ready {
+                                let current = current.clone ();
+                                rt.on_current_instant (Box::new (move |rt: &mut Runtime, ()| {
+                                    ct.call_box (rt, current);
+                                }));
+                            } else {
+                                waiting.push_back ((token,when,ct));
+                            }
                         }
+                        (*data).waiters = waiting;
                     },
                 };
             }));
+            rt.set_token (token);
         }
     }
 
+
+    fn parked (&self, rt: &Runtime) -> Option<Token> {
+        park (rt, &self.data, |data: &Mutex<RefCell<ValueSignalRuntime<A,B>>>, rt: &mut Runtime| {
+            let data = data.lock ().unwrap ();
+            let mut data = data.borrow_mut ();
+            (*data).waiters.retain (|&(ref token,_,_)| is_alive (token, rt));
+        })
+    }
+
+    fn wait (&self, rt: &mut Runtime, when: Option<Box<Fn(&B) -> bool + Send>>,
+             next: Box<Continuation<B> + Send>)
+    {
+        let data = self.data.lock ().unwrap ();
+        let mut data = data.borrow_mut ();
+        let token = self.parked (rt);
+        data.waiters.push_back ((token.clone (), when, guard (token, next)));
+    }
+
 }
 
-impl<A> Signal for ValueSignal<A> 
-where A: Clone + Send + 'static,
+impl<A,B> Signal for ValueSignal<A,B> 
+where A: Send + 'static,
+      B: Clone + Send + 'static,
 {
 
+    fn id (&self) -> usize {
+        self.base.id ()
+    }
+
+    fn call_pre (&self, rt: &mut Runtime) -> bool {
+        self.base.call_pre (rt)
+    }
+
     fn call_await_immediate (&self, rt: &mut Runtime,
                              next: Box<Continuation<()> + Send>)
     {
@@ -218,9 +374,9 @@ where A: Clone + Send + 'static,
 
 }
 
-impl<A> Arrow<A,()> for EmitValueSignal<A>
-where Self: Clone + Send + 'static,
-      A: Clone + Send + 'static,
+impl<A,B> Arrow<A,()> for EmitValueSignal<A,B>
+where A: Send + 'static,
+      B: Clone + Send + 'static,
 {
 
     fn call<F> (&self, rt: &mut Runtime, a: A, next: F)
@@ -230,33 +386,116 @@ where Self: Clone + Send + 'static,
         let data = signal.data.lock ().unwrap ();
         let mut data = data.borrow_mut ();
         signal.awake (rt, &mut *data);
-        let mut temp = Option::None;
-        swap (&mut data.current, &mut temp);
-        match temp {
-            Option::None => {
-                temp = Option::Some (a);
-                swap (&mut data.current, &mut temp);
-            },
-            Option::Some (current) => {
-                temp = Option::Some ((*data.combine) (current, a));
-                swap (&mut data.current, &mut temp);
-            },
+        let current = {
+            let data = &mut *data;
+            (*data.fold) (data.current.take (), a)
         };
+        data.current = Option::Some (current.clone ());
+        if data.last.as_ref ().map (|&(date, _)| date) != Option::Some (rt.clock ()) {
+            data.prev = data.last.take ();
+        }
+        data.last = Option::Some ((rt.clock (), current));
+    }
+
+    fn describe (&self) -> Description {
+        let &EmitValueSignal(ref signal) = self;
+        Description::signal ("emit", vec! [signal.id ()], vec! [])
     }
 
 }
 
-impl<A> Arrow<(),A> for AwaitValueSignal<A>
-where Self: Clone + Send + 'static,
-      A: Send + 'static,
+impl<A,B> Arrow<(),B> for AwaitValueSignal<A,B>
+where A: Send + 'static,
+      B: Clone + Send + 'static,
 {
 
-    fn call<F> (&self, _: &mut Runtime, (): (), next: F)
-    where F: Continuation<A> + Send {
+    fn call<F> (&self, rt: &mut Runtime, (): (), next: F)
+    where F: Continuation<B> + Send {
         let &AwaitValueSignal (ref signal) = self;
-        let data = signal.data.lock ().unwrap ();
-        let mut data = data.borrow_mut ();
-        data.waiters.push_back (Box::new (next));
+        signal.wait (rt, Option::None, Box::new (next));
+    }
+
+    fn describe (&self) -> Description {
+        let &AwaitValueSignal(ref signal) = self;
+        Description::signal ("await", vec! [signal.id ()], vec! [])
+    }
+
+}
+
+impl<A,B,P> Arrow<(),B> for AwaitWhenValueSignal<A,B,P>
+where A: Send + 'static,
+      B: Clone + Send + 'static,
+      P: Fn(&B) -> bool + Send + Sync + 'static,
+{
+
+    fn call<F> (&self, rt: &mut Runtime, (): (), next: F)
+    where F: Continuation<B> + Send {
+        let when = self.when.clone ();
+        self.signal.wait (rt, Option::Some (Box::new (move |b: &B| when (b))), Box::new (next));
+    }
+
+    fn describe (&self) -> Description {
+        Description::signal ("await when", vec! [self.signal.id ()], vec! [])
+    }
+
+}
+
+impl<A,B> Arrow<(),Option<B>> for PreValueSignal<A,B>
+where A: Send + 'static,
+      B: Clone + Send + 'static,
+{
+
+    fn call<F> (&self, rt: &mut Runtime, (): (), next: F)
+    where F: Continuation<Option<B>> + Send {
+        let &PreValueSignal (ref signal) = self;
+        let pre = {
+            let data = signal.data.lock ().unwrap ();
+            let data = data.borrow ();
+            let pre = match data.last {
+                Option::Some ((date, _)) if date == rt.clock () => &data.prev,
+                _ => &data.last,
+            };
+            match *pre {
+                Option::Some ((date, ref v)) if date + 1 == rt.clock () => Option::Some (v.clone ()),
+                _ => Option::None,
+            }
+        };
+        next.call (rt, pre);
+    }
+
+    fn describe (&self) -> Description {
+        let &PreValueSignal(ref signal) = self;
+        Description::signal ("pre value", vec! [signal.id ()], vec! [])
+    }
+
+}
+
+// The value of the last instant before the current one where the signal was
+// emitted, however old it is.
+
+impl<A,B> Arrow<(),Option<B>> for LastValueSignal<A,B>
+where A: Send + 'static,
+      B: Clone + Send + 'static,
+{
+
+    fn call<F> (&self, rt: &mut Runtime, (): (), next: F)
+    where F: Continuation<Option<B>> + Send {
+        let &LastValueSignal (ref signal) = self;
+        let last = {
+            let data = signal.data.lock ().unwrap ();
+            let data = data.borrow ();
+            let last = match data.last {
+                Option::Some ((date, _)) if date == rt.clock () => &data.prev,
+                _ => &data.last,
+            };
+            last.as_ref ().map (|&(_, ref v)| v.clone ())
+        };
+        next.call (rt, last);
+    }
+
+    fn describe (&self) -> Description {
+        let &LastValueSignal(ref signal) = self;
+        Description::signal ("last", vec! [signal.id ()], vec! [])
     }
 
 }
@@ -271,7 +510,7 @@ where Self: Clone + Send + 'static,
 struct UniqSignalRuntime<A> {
     current : Option <A>,
     combine : Box<Fn(A,A) -> A + Send>,
-    waiter  : Option<Box<Continuation<A> + Send>>,
+    waiter  : Option<(Option<Token>,Option<Box<Fn(&A) -> bool + Send>>,Box<Continuation<A> + Send>)>,
     awaken  : bool,
 }
 
@@ -296,6 +535,17 @@ pub struct EmitUniqSignal<A> (UniqSignal<A>);
 
 pub struct AwaitUniqSignal<A> (UniqSignal<A>);
 
+// Given to a process awaiting a UniqSignal while another one already waits for
+// it; the latter keeps waiting.
+
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub struct AlreadyAwaited;
+
+pub struct AwaitWhenUniqSignal<A,P> {
+    signal : UniqSignal<A>,
+    when   : Arc<P>,
+}
+
 impl<A> UniqSignal<A>
 where Self: Clone + 'static,
       A: Send + 'static
@@ -325,6 +575,7 @@ where Self: Clone + 'static,
         if data.awaken {} else {
             data.awaken = true;
             let signal = self.clone ();
+            let token = rt.set_token (Option::None);
             rt.on_end_of_instant (Box::new (move |rt: &mut Runtime, ()| {
                 let data = signal.data.lock ().unwrap ();
                 let mut data = data.borrow_mut ();
@@ -334,14 +585,77 @@ where Self: Clone + 'static,
                 match current {
                     Option::None => {},
                     Option::Some (current) => {
-                        if let Option::Some (ct) = (*data).waiter.take () {
-                            rt.on_current_instant (Box::new (move |rt: &mut Runtime, ()| {
-                                ct.call_box (rt, current);
-                            }));
+                        if let Option::Some ((token,when,ct)) = (*data).waiter.take () {
+                            let ready = match when {
+                                Option::Some (ref when) => when (&current),
+                                Option::None => true,
+                            };
+                            if ready {
+                                rt.on_current_instant (Box::new (move |rt: &mut Runtime, ()| {
+                                    ct.call_box (rt, current);
+                                }));
+                            } else {
+                                (*data).waiter = Option::Some ((token,when,ct));
+                            }
                         }
                     },
                 };
             }));
+            rt.set_token (token);
+        }
+    }
+
+
+    fn parked (&self, rt: &Runtime) -> Option<Token> {
+        park (rt, &self.data, |data: &Mutex<RefCell<UniqSignalRuntime<A>>>, rt: &mut Runtime| {
+            let data = data.lock ().unwrap ();
+            let mut data = data.borrow_mut ();
+            let dead = match (*data).waiter {
+                Option::Some ((ref token,_,_)) => !is_alive (token, rt),
+                Option::None => false,
+            };
+            if dead { (*data).waiter = Option::None; }
+        })
+    }
+
+    fn wait<F> (&self, rt: &mut Runtime, when: Option<Box<Fn(&A) -> bool + Send>>, next: F)
+    where F: Continuation<Result<A,AlreadyAwaited>> + Send
+    {
+        {
+            let data = self.data.lock ().unwrap ();
+            let mut data = data.borrow_mut ();
+            let pending = match data.waiter {
+                Option::Some ((Option::Some (ref token),_,_)) => !token.is_cancelling (),
+                Option::Some ((Option::None,_,_)) => true,
+                Option::None => false,
+            };
+            if !pending {
+                let token = self.parked (rt);
+                let next = Box::new (move |rt: &mut Runtime, a: A| {
+                    next.call (rt, Result::Ok (a));
+                });
+                data.waiter = Option::Some ((token.clone (), when, guard (token, next)));
+                return;
+            }
+        }
+        next.call (rt, Result::Err (AlreadyAwaited));
+    }
+
+}
+
+impl<A> AwaitUniqSignal<A>
+where A: Send + 'static
+{
+
+    // Only resumes in an instant where the value satisfies `when`; the values
+    // of the other instants are dropped.
+    pub fn await_when<P> (self, when: P) -> AwaitWhenUniqSignal<A,P>
+    where P: Fn(&A) -> bool + Send + Sync + 'static
+    {
+        let AwaitUniqSignal (signal) = self;
+        AwaitWhenUniqSignal {
+            signal: signal,
+            when: Arc::new (when),
         }
     }
 
@@ -351,6 +665,14 @@ impl<A> Signal for UniqSignal<A>
 where Self: Clone + Send + 'static,
       A: Send + 'static
 {
+
+    fn id (&self) -> usize {
+        self.base.id ()
+    }
+
+    fn call_pre (&self, rt: &mut Runtime) -> bool {
+        self.base.call_pre (rt)
+    }
     
     fn call_await_immediate (&self, rt: &mut Runtime,
                              next: Box<Continuation<()> + Send>)
@@ -394,19 +716,172 @@ where Self: Clone + Send + 'static,
         };
     }
 
+    fn describe (&self) -> Description {
+        let &EmitUniqSignal(ref signal) = self;
+        Description::signal ("emit", vec! [signal.id ()], vec! [])
+    }
+
 }
 
-impl<A> Arrow<(),A> for AwaitUniqSignal<A>
+impl<A> Arrow<(),Result<A,AlreadyAwaited>> for AwaitUniqSignal<A>
 where Self: Send + 'static,
       A: Send + 'static,
 {
 
-    fn call<F> (&self, _: &mut Runtime, (): (), next: F)
-    where F: Continuation<A> + Send {
+    fn call<F> (&self, rt: &mut Runtime, (): (), next: F)
+    where F: Continuation<Result<A,AlreadyAwaited>> + Send {
         let &AwaitUniqSignal (ref signal) = self;
-        let data = signal.data.lock ().unwrap ();
-        let mut data = data.borrow_mut ();
-        data.waiter = Option::Some (Box::new (next));
+        signal.wait (rt, Option::None, next);
+    }
+
+    fn describe (&self) -> Description {
+        let &AwaitUniqSignal(ref signal) = self;
+        Description::signal ("await", vec! [signal.id ()], vec! [])
+    }
+
+}
+
+impl<A,P> Arrow<(),Result<A,AlreadyAwaited>> for AwaitWhenUniqSignal<A,P>
+where Self: Send + 'static,
+      A: Send + 'static,
+      P: Fn(&A) -> bool + Send + Sync + 'static,
+{
+
+    fn call<F> (&self, rt: &mut Runtime, (): (), next: F)
+    where F: Continuation<Result<A,AlreadyAwaited>> + Send {
+        let when = self.when.clone ();
+        self.signal.wait (rt, Option::Some (Box::new (move |a: &A| when (a))), next);
+    }
+
+    fn describe (&self) -> Description {
+        Description::signal ("await when", vec! [self.signal.id ()], vec! [])
+    }
+
+}
+
+//  ____       _                 _                  
+// | __ )  ___| |__   __ ___   _(_) ___  _   _ _ __ 
+// |  _ \ / _ \ '_ \ / _` \ \ / / |/ _ \| | | | '__|
+// | |_) |  __/ | | | (_| |\ V /| | (_) | |_| | |   
+// |____/ \___|_| |_|\__,_| \_/ |_|\___/ \__,_|_|   
+//                                                  
+
+// A value which can be read at any time, from processes or from another
+// thread. Updates take effect at the next instant; when several happen in the
+// same instant the last one wins.
+
+struct BehaviourData<A> {
+    value   : A,
+    pending : Option<(u64,A)>,
+    awaken  : bool,
+}
+
+impl<A> BehaviourData<A> {
+
+    fn commit (&mut self, clock: u64) {
+        let ready = match self.pending {
+            Option::Some ((date, _)) => date < clock,
+            Option::None => false,
+        };
+        if ready {
+            let (_, value) = self.pending.take ().unwrap ();
+            self.value = value;
+        }
+    }
+
+}
+
+pub struct Behaviour<A> {
+    data: Arc<Mutex<BehaviourData<A>>>,
+}
+
+impl<A> Clone for Behaviour<A> {
+
+    fn clone (&self) -> Behaviour<A> {
+        Behaviour {data: self.data.clone ()}
+    }
+
+}
+
+pub struct GetBehaviour<A> (Behaviour<A>);
+
+pub struct SetBehaviour<A> (Behaviour<A>);
+
+impl<A> Behaviour<A>
+where A: Clone + Send + 'static
+{
+
+    pub fn new (value: A) -> Behaviour<A> {
+        Behaviour {data: Arc::new (Mutex::new (BehaviourData {
+            value   : value,
+            pending : Option::None,
+            awaken  : false,
+        }))}
+    }
+
+    pub fn get (&self) -> GetBehaviour<A> {
+        GetBehaviour (self.clone ())
+    }
+
+    pub fn set (&self) -> SetBehaviour<A> {
+        SetBehaviour (self.clone ())
+    }
+
+    pub fn read (&self) -> A {
+        self.data.lock ().unwrap ().value.clone ()
+    }
+
+}
+
+impl<A> Arrow<(),A> for GetBehaviour<A>
+where A: Clone + Send + 'static
+{
+
+    fn call<F> (&self, rt: &mut Runtime, (): (), next: F)
+    where F: Continuation<A> + Send {
+        let &GetBehaviour (ref cell) = self;
+        let value = {
+            let mut data = cell.data.lock ().unwrap ();
+            data.commit (rt.clock ());
+            data.value.clone ()
+        };
+        next.call (rt, value);
+    }
+
+    fn describe (&self) -> Description {
+        Description::new ("get")
+    }
+
+}
+
+impl<A> Arrow<A,()> for SetBehaviour<A>
+where A: Clone + Send + 'static
+{
+
+    fn call<F> (&self, rt: &mut Runtime, a: A, next: F)
+    where F: Continuation<()> + Send {
+        let &SetBehaviour (ref cell) = self;
+        {
+            let mut data = cell.data.lock ().unwrap ();
+            data.commit (rt.clock ());
+            data.pending = Option::Some ((rt.clock (), a));
+            if ! data.awaken {
+                data.awaken = true;
+                let data = cell.data.clone ();
+                let token = rt.set_token (Option::None);
+                rt.on_end_of_instant (Box::new (move |rt: &mut Runtime, ()| {
+                    let mut data = data.lock ().unwrap ();
+                    data.awaken = false;
+                    data.commit (rt.clock ());
+                }));
+                rt.set_token (token);
+            }
+        }
+        next.call (rt, ());
+    }
+
+    fn describe (&self) -> Description {
+        Description::new ("set")
     }
 
 }